@@ -79,6 +79,21 @@ fn probe_one_usrp(address: &str) -> Result<(), Box<dyn Error>> {
         if let Ok(antennas) = usrp.get_tx_antennas(channel) {
             println!("TX antennas {:?}", antennas);
         }
+        if let Ok(antenna) = usrp.get_tx_antenna(channel) {
+            println!("Current TX antenna {}", antenna);
+        }
+        if let Ok(frequency) = usrp.get_tx_frequency(channel) {
+            println!("Current TX frequency {}", frequency);
+        }
+        if let Ok(rate) = usrp.get_tx_sample_rate(channel) {
+            println!("Current TX sample rate {}", rate);
+        }
+        if let Ok(rates) = usrp.get_tx_sample_rates(channel) {
+            println!("TX sample rates: {:?}", rates);
+        }
+        if let Ok(bandwidth) = usrp.get_tx_bandwidth(channel) {
+            println!("Current TX bandwidth {}", bandwidth);
+        }
         if let Ok(range) = usrp.get_fe_tx_freq_range(channel) {
             println!("Front-end TX frequency ranges: {:?}", range);
         }
@@ -105,6 +120,21 @@ fn probe_one_usrp(address: &str) -> Result<(), Box<dyn Error>> {
         if let Ok(antennas) = usrp.get_rx_antennas(channel) {
             println!("RX antennas {:?}", antennas);
         }
+        if let Ok(antenna) = usrp.get_rx_antenna(channel) {
+            println!("Current RX antenna {}", antenna);
+        }
+        if let Ok(frequency) = usrp.get_rx_frequency(channel) {
+            println!("Current RX frequency {}", frequency);
+        }
+        if let Ok(rate) = usrp.get_rx_sample_rate(channel) {
+            println!("Current RX sample rate {}", rate);
+        }
+        if let Ok(rates) = usrp.get_rx_sample_rates(channel) {
+            println!("RX sample rates: {:?}", rates);
+        }
+        if let Ok(bandwidth) = usrp.get_rx_bandwidth(channel) {
+            println!("Current RX bandwidth {}", bandwidth);
+        }
         if let Ok(range) = usrp.get_fe_rx_freq_range(channel) {
             println!("Front-end RX frequency ranges: {:?}", range);
         }
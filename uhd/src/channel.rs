@@ -0,0 +1,61 @@
+use std::fmt;
+
+use crate::error::Error;
+
+/// A channel index that has been checked against the number of channels of a USRP
+///
+/// A Channel can be obtained from Usrp::rx_channel() or Usrp::tx_channel(), which return
+/// Error::InvalidChannel for an index that is out of range. The per-channel functions of
+/// Usrp take a usize index, which can be obtained with index() or into().
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Channel(usize);
+
+impl Channel {
+    /// Returns a channel if index is less than num_channels, or Error::InvalidChannel
+    /// otherwise
+    pub(crate) fn checked(index: usize, num_channels: usize) -> Result<Self, Error> {
+        if index < num_channels {
+            Ok(Channel(index))
+        } else {
+            Err(Error::InvalidChannel {
+                channel: index,
+                num_channels,
+            })
+        }
+    }
+
+    /// Returns the index of this channel
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl From<Channel> for usize {
+    fn from(channel: Channel) -> Self {
+        channel.0
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Channel;
+    use crate::Error;
+
+    #[test]
+    fn checked_index() {
+        assert_eq!(Channel::checked(1, 2).unwrap().index(), 1);
+        assert!(matches!(
+            Channel::checked(2, 2),
+            Err(Error::InvalidChannel {
+                channel: 2,
+                num_channels: 2
+            })
+        ));
+    }
+}
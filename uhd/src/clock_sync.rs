@@ -0,0 +1,61 @@
+use std::convert::TryFrom;
+
+use crate::error::Error;
+
+/// A matching pair of clock (frequency reference) and time (PPS) sources
+///
+/// Using a clock source from one device and a time source from another is a common cause of
+/// incoherent multi-USRP captures. Usrp::configure_clock_sync() sets both sources from one of
+/// these values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockSync {
+    /// The USRP's internal oscillator, with no PPS input
+    Internal,
+    /// Reference and PPS signals from the external inputs
+    External,
+    /// Reference and PPS signals from an installed GPS disciplined oscillator
+    Gpsdo,
+    /// Reference and PPS signals from another USRP through a MIMO cable
+    MimoCable,
+}
+
+impl ClockSync {
+    /// Returns the name of the clock source that UHD uses for this configuration
+    pub fn clock_source(&self) -> &'static str {
+        match self {
+            ClockSync::Internal => "internal",
+            ClockSync::External => "external",
+            ClockSync::Gpsdo => "gpsdo",
+            ClockSync::MimoCable => "mimo",
+        }
+    }
+
+    /// Returns the name of the time source that UHD uses for this configuration
+    pub fn time_source(&self) -> &'static str {
+        match self {
+            ClockSync::Internal => "internal",
+            ClockSync::External => "external",
+            ClockSync::Gpsdo => "gpsdo",
+            ClockSync::MimoCable => "mimo",
+        }
+    }
+}
+
+impl TryFrom<&str> for ClockSync {
+    type Error = Error;
+
+    /// Parses a configuration from the name of its clock and time sources (`internal`,
+    /// `external`, `gpsdo`, or `mimo`)
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "internal" => Ok(ClockSync::Internal),
+            "external" => Ok(ClockSync::External),
+            "gpsdo" => Ok(ClockSync::Gpsdo),
+            "mimo" => Ok(ClockSync::MimoCable),
+            _ => Err(Error::UnknownVariant {
+                value: value.to_owned(),
+                expected: "internal, external, gpsdo, mimo",
+            }),
+        }
+    }
+}
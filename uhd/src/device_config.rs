@@ -0,0 +1,48 @@
+use std::collections::BTreeMap;
+
+use crate::SensorValue;
+
+/// A snapshot of the settings of a USRP, from Usrp::dump_config()
+///
+/// When the `serde` feature is enabled, this can be serialized (for example, to JSON) and
+/// included in a bug report or saved with recorded samples.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceConfig {
+    /// The settings of each motherboard, in motherboard index order
+    pub motherboards: Vec<MotherboardConfig>,
+    /// The settings of each receive channel, in channel index order
+    pub rx_channels: Vec<ChannelConfig>,
+    /// The settings of each transmit channel, in channel index order
+    pub tx_channels: Vec<ChannelConfig>,
+}
+
+/// The settings of one motherboard
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MotherboardConfig {
+    /// Clock source name
+    pub clock_source: String,
+    /// Time source name
+    pub time_source: String,
+    /// Master clock rate, in hertz
+    pub master_clock_rate: f64,
+    /// The values of all motherboard sensors when the snapshot was taken
+    pub sensors: BTreeMap<String, SensorValue>,
+}
+
+/// The settings of one receive or transmit channel
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelConfig {
+    /// Center frequency, in hertz
+    pub frequency: f64,
+    /// Sample rate, in samples per second
+    pub sample_rate: f64,
+    /// Overall gain, in decibels
+    pub gain: f64,
+    /// Antenna name
+    pub antenna: String,
+    /// Front-end bandwidth, in hertz
+    pub bandwidth: f64,
+}
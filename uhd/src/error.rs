@@ -1,6 +1,7 @@
 use crate::utils::copy_string;
 use std::ffi::NulError;
 use std::str::Utf8Error;
+use std::time::Duration;
 
 use thiserror::Error as ThisError;
 
@@ -77,6 +78,139 @@ pub enum Error {
     #[error("String from FFI contains invalid UTF-8")]
     Utf8,
 
+    /// Buffers for different channels had different lengths
+    #[error("Buffers for different channels have different lengths")]
+    UnequalBufferLengths,
+
+    /// A DSP frequency offset is larger than the DSP can apply without an RF retune
+    #[error("DSP offset {offset} Hz on channel {channel} is outside the range +/-{limit} Hz")]
+    DspOffsetOutOfRange {
+        channel: usize,
+        offset: f64,
+        limit: f64,
+    },
+
+    /// Receive or transmit channels have different sample rates after setting the same rate
+    #[error("Channels have different sample rates: {rates:?}")]
+    RateMismatch { rates: Vec<f64> },
+
+    /// A buffer has more samples than the C API can accept in one call
+    #[error("Buffer of {length} samples is too large to pass to UHD")]
+    BufferTooLarge { length: usize },
+
+    /// The USRP ran out of samples to transmit in the middle of a burst
+    #[error("Transmit underflow")]
+    Underflow,
+
+    /// A TimeSpec had a fraction outside the range [0, 1)
+    #[error("Time fraction is not in the range [0, 1)")]
+    InvalidTimeSpec,
+
+    /// A tick rate was not finite or was less than 1 Hz
+    #[error("Tick rate {0} Hz is not a finite rate of at least 1 Hz")]
+    InvalidTickRate(f64),
+
+    /// A sample index was too large to convert into a time
+    #[error("Sample index {0} is too large to convert into a time")]
+    SampleIndexTooLarge(u64),
+
+    /// A motherboard did not lock to its reference clock before a timeout
+    #[error("Motherboard {mboard} did not lock to its reference clock within {elapsed:?}")]
+    LockTimeout { mboard: usize, elapsed: Duration },
+
+    /// The local oscillator of a channel did not lock before a timeout
+    #[error("Local oscillator of channel {channel} did not lock within {elapsed:?}")]
+    LoLockTimeout { channel: usize, elapsed: Duration },
+
+    /// Channels that should share a local oscillator do not have exactly one channel that
+    /// exports it, or have a channel that is not importing it
+    #[error("Invalid LO chain: exporting channels {exporters:?}, channels using their own LO without exporting {unshared:?}")]
+    InvalidLoChain {
+        exporters: Vec<usize>,
+        unshared: Vec<usize>,
+    },
+
+    /// A temperature reached the limit set in a ThermalGuard
+    #[error("Temperature of transmit channel {channel} is {temperature} degrees C, limit is {limit} degrees C")]
+    Overheated {
+        channel: usize,
+        temperature: f64,
+        limit: f64,
+    },
+
+    /// A device does not have a sensor that an operation needs
+    #[error("Motherboard {mboard} has no sensor named \"{name}\"")]
+    MissingSensor { name: String, mboard: usize },
+
+    /// A motherboard's time at the last PPS edge was not the time that was set for that edge
+    #[error("Motherboard {0} did not set its time at the PPS edge")]
+    PpsTimeNotSet(usize),
+
+    /// A burst or command was scheduled for a time that had already passed when it reached
+    /// the device
+    ///
+    /// When receiving, a late stream command is reported in the metadata as
+    /// ReceiveErrorKind::LateCommand instead.
+    #[error("Command time was in the past")]
+    LateCommand,
+
+    /// An operation did not complete before its timeout
+    #[error("Operation timed out")]
+    Timeout,
+
+    /// The firmware or FPGA image on a device is not compatible with this version of UHD
+    ///
+    /// Running `uhd_images_downloader` and then loading the new images onto the device usually
+    /// fixes this.
+    #[error("Device image is not compatible with this version of UHD (try uhd_images_downloader): {detail}")]
+    ImageMismatch { detail: String },
+
+    /// An antenna name is not one of the antennas available on a channel
+    #[error("Invalid antenna \"{antenna}\", available antennas: {available:?}")]
+    InvalidAntenna {
+        antenna: String,
+        available: Vec<String>,
+    },
+
+    /// A channel index is not less than the number of channels
+    #[error("Channel {channel} is out of range, the USRP has {num_channels} channel(s)")]
+    InvalidChannel { channel: usize, num_channels: usize },
+
+    /// A capture to a writer failed after some samples had been written
+    ///
+    /// The writer has been flushed, so it holds the first `written` samples of the capture.
+    #[error("Capture failed after {written} samples: {source}")]
+    PartialCapture {
+        written: usize,
+        #[source]
+        source: Box<Error>,
+    },
+
+    /// An operation on one channel of several failed
+    #[error("Channel {channel}: {source}")]
+    OnChannel {
+        channel: usize,
+        #[source]
+        source: Box<Error>,
+    },
+
+    /// UHD cannot stream with a combination of host and wire formats
+    ///
+    /// Which formats are available depends on the device and the UHD version.
+    #[error("Unsupported stream formats (host {host_format}, wire {wire_format}): {detail}")]
+    UnsupportedFormat {
+        host_format: &'static str,
+        wire_format: String,
+        detail: String,
+    },
+
+    /// A string did not match any of the accepted names of an enum
+    #[error("Unknown value \"{value}\", expected one of: {expected}")]
+    UnknownVariant {
+        value: String,
+        expected: &'static str,
+    },
+
     #[error("Unknown error")]
     Unknown,
 
@@ -89,6 +223,26 @@ pub fn last_error_message() -> Option<String> {
     copy_string(|buffer, length| unsafe { uhd_sys::uhd_get_last_error(buffer, length as _) }).ok()
 }
 
+/// Returns true if a UHD error message reports a firmware or FPGA image that is not compatible
+/// with the UHD version
+pub(crate) fn is_image_mismatch(message: &str) -> bool {
+    const PATTERNS: [&str; 4] = [
+        "Expected firmware",
+        "Expected FPGA",
+        "Please update",
+        "uhd_images_downloader",
+    ];
+    PATTERNS.iter().any(|pattern| message.contains(pattern))
+}
+
+/// Returns true if a UHD error message reports that a stream cannot use the requested host or
+/// wire format
+pub(crate) fn is_unsupported_format(message: &str) -> bool {
+    const PATTERNS: [&str; 2] = ["conversion routine", "otw format"];
+    let message = message.to_lowercase();
+    PATTERNS.iter().any(|pattern| message.contains(pattern))
+}
+
 /// Converts a status code into a result
 pub(crate) fn check_status(status: uhd_sys::uhd_error::Type) -> Result<()> {
     use uhd_sys::uhd_error;
@@ -131,3 +285,33 @@ impl From<Utf8Error> for Error {
         Error::Utf8
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{is_image_mismatch, is_unsupported_format};
+
+    #[test]
+    fn image_mismatch_messages() {
+        assert!(is_image_mismatch(
+            "RuntimeError: Expected FPGA compatibility number 38, but got 37:\n\
+             The FPGA image on your device is not compatible with this host code build.\n\
+             Download the appropriate FPGA images for this version of UHD.\n\
+             Please run:\n\n \"/usr/lib/uhd/utils/uhd_images_downloader.py\""
+        ));
+        assert!(!is_image_mismatch(
+            "LookupError: KeyError: No devices found"
+        ));
+    }
+
+    #[test]
+    fn unsupported_format_messages() {
+        assert!(is_unsupported_format(
+            "LookupError: KeyError: Cannot find a conversion routine for conversion ID\n  \
+             Input format: sc12_item32_le\n  Num inputs: 1\n  Output format: fc64"
+        ));
+        assert!(is_unsupported_format(
+            "ValueError: Unsupported OTW format: sc12"
+        ));
+        assert!(!is_unsupported_format("IndexError: channel out of range"));
+    }
+}
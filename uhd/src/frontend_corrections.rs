@@ -0,0 +1,23 @@
+/// Automatic correction settings for a receive front end
+///
+/// UHD can set these options but cannot read them back, and it does not provide the correction
+/// values that the device has converged to. To restore the same settings on a later run, keep
+/// a copy of this struct and apply it with Usrp::set_rx_frontend_corrections().
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrontendCorrections {
+    /// Automatic DC offset correction
+    pub dc_offset_enabled: bool,
+    /// Automatic IQ imbalance correction
+    pub iq_balance_enabled: bool,
+}
+
+impl Default for FrontendCorrections {
+    /// Returns settings with both corrections enabled, which is the UHD default for devices
+    /// that support them
+    fn default() -> Self {
+        FrontendCorrections {
+            dc_offset_enabled: true,
+            iq_balance_enabled: true,
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//!
+//! Conversion between interleaved real slices and complex slices without copying
+//!
+//! `Complex<T>` is `#[repr(C)]` with the real part followed by the imaginary part, so a slice
+//! of complex samples has the same layout as a slice of twice as many interleaved
+//! in-phase/quadrature values (I, Q, I, Q, ...) with the same alignment. These functions
+//! reinterpret one as the other.
+//!
+
+use num_complex::Complex;
+
+/// Views interleaved in-phase/quadrature values as complex samples
+///
+/// This function returns None if the length of values is odd.
+pub fn as_complex_slice<T>(values: &[T]) -> Option<&[Complex<T>]> {
+    if values.len() % 2 == 1 {
+        return None;
+    }
+    // Complex<T> is repr(C) with two fields of type T, so it has the size of two Ts and the
+    // alignment of T.
+    Some(unsafe {
+        std::slice::from_raw_parts(values.as_ptr() as *const Complex<T>, values.len() / 2)
+    })
+}
+
+/// Views mutable interleaved in-phase/quadrature values as complex samples
+///
+/// This function returns None if the length of values is odd.
+pub fn as_complex_slice_mut<T>(values: &mut [T]) -> Option<&mut [Complex<T>]> {
+    if values.len() % 2 == 1 {
+        return None;
+    }
+    Some(unsafe {
+        std::slice::from_raw_parts_mut(values.as_mut_ptr() as *mut Complex<T>, values.len() / 2)
+    })
+}
+
+/// Views complex samples as interleaved in-phase/quadrature values
+pub fn as_interleaved_slice<T>(samples: &[Complex<T>]) -> &[T] {
+    unsafe { std::slice::from_raw_parts(samples.as_ptr() as *const T, samples.len() * 2) }
+}
+
+/// Views mutable complex samples as interleaved in-phase/quadrature values
+pub fn as_interleaved_slice_mut<T>(samples: &mut [Complex<T>]) -> &mut [T] {
+    unsafe { std::slice::from_raw_parts_mut(samples.as_mut_ptr() as *mut T, samples.len() * 2) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num_complex::Complex32;
+
+    #[test]
+    fn layout_matches() {
+        let mut values = [1.0f32, 2.0, 3.0, -4.0];
+        assert_eq!(
+            as_complex_slice(&values),
+            Some(&[Complex32::new(1.0, 2.0), Complex32::new(3.0, -4.0)][..])
+        );
+        as_complex_slice_mut(&mut values).unwrap()[1].im = 5.0;
+        assert_eq!(values, [1.0, 2.0, 3.0, 5.0]);
+        assert_eq!(as_complex_slice(&values[..3]), None);
+
+        let mut samples = [Complex32::new(0.5, -0.25)];
+        assert_eq!(as_interleaved_slice(&samples), &[0.5, -0.25]);
+        as_interleaved_slice_mut(&mut samples)[0] = 1.5;
+        assert_eq!(samples[0], Complex32::new(1.5, -0.25));
+
+        // Byte layout: real part bytes, then imaginary part bytes, in native byte order
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                samples.as_ptr() as *const u8,
+                std::mem::size_of_val(&samples),
+            )
+        };
+        let expected = [1.5f32.to_ne_bytes(), (-0.25f32).to_ne_bytes()].concat();
+        assert_eq!(bytes, &expected[..]);
+    }
+}
@@ -16,39 +16,66 @@ extern crate libc;
 extern crate num_complex;
 extern crate uhd_sys;
 
+mod channel;
+mod clock_sync;
 mod daughter_board_eeprom;
+mod device_config;
 mod error;
+mod frontend_corrections;
+pub mod interleaved;
+mod lo_chain;
+#[cfg(feature = "mock")]
+pub mod mock;
 mod motherboard_eeprom;
 pub mod range;
 mod receiver;
+mod sample_file;
+mod scheduler;
+mod sensor;
 mod stream;
 mod string_vector;
+mod subdev_spec;
+mod thermal_guard;
+mod thread_priority;
+mod time_spec;
 mod transmitter;
 mod tune_request;
 mod tune_result;
 mod usrp;
 mod utils;
+pub mod waveform;
 
 // Re-export many public items at the root
+pub use channel::Channel;
+pub use clock_sync::ClockSync;
 pub use daughter_board_eeprom::DaughterBoardEeprom;
+pub use device_config::{ChannelConfig, DeviceConfig, MotherboardConfig};
 pub use error::*;
+pub use frontend_corrections::FrontendCorrections;
+pub use lo_chain::{LoChain, LoChainEntry};
 pub use motherboard_eeprom::MotherboardEeprom;
-pub use receiver::{info::ReceiveInfo, metadata::*, streamer::ReceiveStreamer};
+pub use receiver::{
+    buffer_pool::{BufferPool, PooledBuffer},
+    burst::BurstAccumulator,
+    info::ReceiveInfo,
+    metadata::*,
+    streamer::{DiscardStats, PooledSamples, ReceiveStreamer},
+};
+pub use sample_file::FileSample;
+pub use scheduler::{ScheduledAction, Scheduler};
+pub use sensor::SensorValue;
 pub use stream::*;
-pub use transmitter::{info::TransmitInfo, metadata::*, streamer::TransmitStreamer};
+pub use subdev_spec::{SubdevSpec, SubdevSpecPair};
+pub use thermal_guard::ThermalGuard;
+pub use thread_priority::{set_thread_priority, thread_priority};
+pub use time_spec::TimeSpec;
+pub use transmitter::{
+    async_metadata::*,
+    info::TransmitInfo,
+    metadata::*,
+    streamer::{AsyncMessages, TransmitStreamer},
+};
 pub use tune_request::*;
 pub use tune_result::TuneResult;
 pub use usrp::Usrp;
 pub use utils::alloc_boxed_slice;
-// Common definitions
-
-/// A time value, represented as an integer number of seconds and a floating-point fraction of
-/// a second
-#[derive(Debug, Clone, Default, PartialOrd, PartialEq)]
-pub struct TimeSpec {
-    // In some versions of UHD, the corresponding field of uhd::time_spec_t is a time_t.
-    // In other versions, it's a int64_t. The Rust code does conversion to keep this
-    // an i64.
-    pub seconds: i64,
-    pub fraction: f64,
-}
@@ -0,0 +1,22 @@
+/// How a local oscillator is shared between receive channels, from Usrp::verify_lo_chain()
+///
+/// In a valid chain, one channel generates the local oscillator with its own synthesizer and
+/// exports it, and every other channel imports it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoChain {
+    /// The channel that exports its local oscillator
+    pub exporter: usize,
+    /// The LO source of each channel, in the order the channels were provided
+    pub sources: Vec<LoChainEntry>,
+}
+
+/// The local oscillator configuration of one channel in an LoChain
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoChainEntry {
+    /// The channel index
+    pub channel: usize,
+    /// The LO source of the channel (for example, `internal` or `companion`)
+    pub source: String,
+    /// True if this channel exports its local oscillator
+    pub exported: bool,
+}
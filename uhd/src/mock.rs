@@ -0,0 +1,308 @@
+//!
+//! An in-memory loopback USRP for testing without hardware
+//!
+//! A `MockUsrp` has a sample queue for each channel. Samples transmitted on a channel are
+//! appended to that channel's queue, and samples received on a channel are taken from the front
+//! of it. This makes it possible to test signal-processing code that uses streamers without
+//! a radio.
+//!
+//! The mock streamers have the same method signatures as `TransmitStreamer` and
+//! `ReceiveStreamer`. They do not call any UHD functions: the metadata that they return is
+//! created without UHD, and has no time, no burst flags, and no error. The crate still links
+//! to the UHD library, so it must be installed to build the tests.
+//!
+
+use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+use crate::error::Error;
+use crate::stream::{Item, StreamArgs, StreamArgsC, StreamCommand, Streamer};
+use crate::utils::check_equal_buffer_lengths;
+use crate::{ReceiveMetadata, TransmitMetadata};
+
+/// An in-memory USRP that sends transmitted samples back to its receive streams
+pub struct MockUsrp<I> {
+    /// One queue of samples for each channel
+    queues: Mutex<Vec<VecDeque<I>>>,
+}
+
+impl<I> MockUsrp<I>
+where
+    I: Item + Clone,
+{
+    /// Creates a mock USRP with the provided number of channels and no queued samples
+    pub fn new(num_channels: usize) -> Self {
+        MockUsrp {
+            queues: Mutex::new((0..num_channels).map(|_| VecDeque::new()).collect()),
+        }
+    }
+
+    /// Returns the number of channels that this mock USRP has
+    pub fn num_channels(&self) -> usize {
+        self.queues.lock().unwrap().len()
+    }
+
+    /// Returns the number of samples waiting to be received on a channel
+    ///
+    /// This function returns an error if the channel index is out of range.
+    pub fn queued_samples(&self, channel: usize) -> Result<usize, Error> {
+        self.queues
+            .lock()
+            .unwrap()
+            .get(channel)
+            .map(VecDeque::len)
+            .ok_or(Error::Index)
+    }
+
+    /// Opens a stream that can be used to receive samples
+    ///
+    /// This function returns an error if any of the channel indexes in the stream arguments
+    /// is out of range.
+    pub fn get_rx_stream(&self, args: &StreamArgs<I>) -> Result<MockReceiveStreamer<'_, I>, Error> {
+        Ok(MockReceiveStreamer {
+            usrp: self,
+            channels: self.check_channels(args)?,
+            item_phantom: PhantomData,
+        })
+    }
+
+    /// Opens a stream that can be used to transmit samples
+    ///
+    /// This function returns an error if any of the channel indexes in the stream arguments
+    /// is out of range.
+    pub fn get_tx_stream(
+        &self,
+        args: &StreamArgs<I>,
+    ) -> Result<MockTransmitStreamer<'_, I>, Error> {
+        Ok(MockTransmitStreamer {
+            usrp: self,
+            channels: self.check_channels(args)?,
+            item_phantom: PhantomData,
+        })
+    }
+
+    /// Returns the channels from the stream arguments (or channel 0 if the list is empty), or
+    /// an error if any channel is out of range
+    fn check_channels(&self, args: &StreamArgs<I>) -> Result<Vec<usize>, Error> {
+        let args: StreamArgsC = args.try_into()?;
+        let channels = if args.channels.is_empty() {
+            vec![0]
+        } else {
+            args.channels.to_vec()
+        };
+        let num_channels = self.num_channels();
+        if channels.iter().all(|&channel| channel < num_channels) {
+            Ok(channels)
+        } else {
+            Err(Error::Index)
+        }
+    }
+}
+
+/// A streamer that receives samples from a MockUsrp
+pub struct MockReceiveStreamer<'usrp, I> {
+    usrp: &'usrp MockUsrp<I>,
+    /// Indexes of the channels that this streamer was created with
+    channels: Vec<usize>,
+    /// Item type phantom data
+    item_phantom: PhantomData<I>,
+}
+
+impl<I> MockReceiveStreamer<'_, I>
+where
+    I: Clone,
+{
+    /// Accepts and ignores a stream command
+    ///
+    /// Samples are available to receive as soon as they are transmitted, so stream commands
+    /// have no effect.
+    pub fn send_command(&mut self, _command: &StreamCommand) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Returns the indexes of the channels that this streamer is associated with
+    pub fn channels(&self) -> &[usize] {
+        &self.channels
+    }
+
+    /// Returns the number of channels that this streamer is associated with
+    pub fn num_channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Receives samples from the queues of this streamer's channels
+    ///
+    /// This function copies as many samples as are queued on every channel, up to the buffer
+    /// length. It never waits, so the timeout and one_packet arguments are ignored. If no samples
+    /// are queued, the returned metadata reports 0 samples.
+    ///
+    /// Like ReceiveStreamer::receive(), this function panics if the number of buffers is not
+    /// equal to self.num_channels(), or if not all buffers have the same length.
+    pub fn receive(
+        &mut self,
+        buffers: &mut [&mut [I]],
+        _timeout: f64,
+        _one_packet: bool,
+    ) -> Result<ReceiveMetadata, Error> {
+        assert_eq!(
+            buffers.len(),
+            self.channels.len(),
+            "Streamer configured for channels {:?} but got {} buffer(s)",
+            self.channels,
+            buffers.len()
+        );
+        let buffer_length = check_equal_buffer_lengths(buffers);
+
+        let mut queues = self.usrp.queues.lock().unwrap();
+        let samples = self
+            .channels
+            .iter()
+            .map(|&channel| queues[channel].len())
+            .fold(buffer_length, usize::min);
+        for (&channel, buffer) in self.channels.iter().zip(buffers.iter_mut()) {
+            for (entry, sample) in buffer.iter_mut().zip(queues[channel].drain(..samples)) {
+                *entry = sample;
+            }
+        }
+
+        Ok(ReceiveMetadata::without_handle(samples))
+    }
+
+    /// Receives samples on a single channel with a timeout of 0.1 seconds and one_packet disabled
+    pub fn receive_simple(&mut self, buffer: &mut [I]) -> Result<ReceiveMetadata, Error> {
+        self.receive(&mut [buffer], 0.1, false)
+    }
+}
+
+impl<I> Streamer for MockReceiveStreamer<'_, I>
+where
+    I: Clone,
+{
+    type Item = I;
+
+    fn channels(&self) -> &[usize] {
+        MockReceiveStreamer::channels(self)
+    }
+
+    fn num_channels(&self) -> usize {
+        MockReceiveStreamer::num_channels(self)
+    }
+
+    fn send_command(&mut self, command: &StreamCommand) -> Result<(), Error> {
+        MockReceiveStreamer::send_command(self, command)
+    }
+}
+
+/// A streamer that transmits samples to a MockUsrp
+pub struct MockTransmitStreamer<'usrp, I> {
+    usrp: &'usrp MockUsrp<I>,
+    /// Indexes of the channels that this streamer was created with
+    channels: Vec<usize>,
+    /// Item type phantom data
+    item_phantom: PhantomData<I>,
+}
+
+impl<I> MockTransmitStreamer<'_, I>
+where
+    I: Clone,
+{
+    /// Returns the indexes of the channels that this streamer is associated with
+    pub fn channels(&self) -> &[usize] {
+        &self.channels
+    }
+
+    /// Returns the number of channels that this streamer is associated with
+    pub fn num_channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Appends samples to the queues of this streamer's channels
+    ///
+    /// All samples are accepted immediately, so the timeout is ignored.
+    ///
+    /// Like TransmitStreamer::transmit(), this function panics if the number of buffers is not
+    /// equal to self.num_channels(), or if not all buffers have the same length.
+    pub fn transmit(
+        &mut self,
+        buffers: &mut [&[I]],
+        _timeout: f64,
+    ) -> Result<TransmitMetadata, Error> {
+        assert_eq!(
+            buffers.len(),
+            self.channels.len(),
+            "Streamer configured for channels {:?} but got {} buffer(s)",
+            self.channels,
+            buffers.len()
+        );
+        let buffer_length = check_equal_buffer_lengths(buffers);
+
+        let mut queues = self.usrp.queues.lock().unwrap();
+        for (&channel, buffer) in self.channels.iter().zip(buffers.iter()) {
+            queues[channel].extend(buffer.iter().cloned());
+        }
+
+        Ok(TransmitMetadata::without_handle(buffer_length))
+    }
+
+    /// Transmits samples on a single channel with a timeout of 0.1 seconds
+    pub fn transmit_simple(&mut self, buffer: &mut [I]) -> Result<TransmitMetadata, Error> {
+        self.transmit(&mut [buffer], 0.1)
+    }
+}
+
+impl<I> Streamer for MockTransmitStreamer<'_, I>
+where
+    I: Clone,
+{
+    type Item = I;
+
+    fn channels(&self) -> &[usize] {
+        MockTransmitStreamer::channels(self)
+    }
+
+    fn num_channels(&self) -> usize {
+        MockTransmitStreamer::num_channels(self)
+    }
+
+    /// Returns Error::NotImplemented, like TransmitStreamer
+    fn send_command(&mut self, _command: &StreamCommand) -> Result<(), Error> {
+        Err(Error::NotImplemented)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MockUsrp;
+    use crate::StreamArgs;
+    use num_complex::Complex32;
+
+    #[test]
+    fn transmitted_samples_are_received() {
+        let usrp = MockUsrp::<Complex32>::new(2);
+        let args = StreamArgs::builder().channels(vec![0, 1]).build();
+        let mut tx = usrp.get_tx_stream(&args).unwrap();
+        let mut rx = usrp.get_rx_stream(&args).unwrap();
+
+        let tx_0 = [Complex32::new(1.0, 0.0), Complex32::new(2.0, 0.0)];
+        let tx_1 = [Complex32::new(0.0, 1.0), Complex32::new(0.0, 2.0)];
+        let sent = tx.transmit(&mut [&tx_0, &tx_1], 0.1).unwrap();
+        assert_eq!(sent.samples(), 2);
+
+        let mut rx_0 = [Complex32::default(); 4];
+        let mut rx_1 = [Complex32::default(); 4];
+        let received = rx.receive(&mut [&mut rx_0, &mut rx_1], 0.1, false).unwrap();
+        assert_eq!(received.samples(), 2);
+        assert_eq!(&rx_0[..2], &tx_0);
+        assert_eq!(&rx_1[..2], &tx_1);
+        assert_eq!(usrp.queued_samples(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn out_of_range_channel() {
+        let usrp = MockUsrp::<Complex32>::new(1);
+        let args = StreamArgs::builder().channels(vec![1]).build();
+        assert!(usrp.get_rx_stream(&args).is_err());
+    }
+}
@@ -5,6 +5,29 @@ use std::ptr;
 #[derive(Clone)]
 pub struct Range(uhd_sys::uhd_range_t);
 
+impl Range {
+    /// Creates a range with the provided start, stop, and step values
+    pub fn new(start: f64, stop: f64, step: f64) -> Self {
+        Range(uhd_sys::uhd_range_t { start, stop, step })
+    }
+
+    /// Returns the start (minimum) of this range
+    pub fn start(&self) -> f64 {
+        self.0.start
+    }
+
+    /// Returns the stop (maximum) of this range
+    pub fn stop(&self) -> f64 {
+        self.0.stop
+    }
+
+    /// Returns the step between legal values in this range, or 0.0 if any value between start
+    /// and stop is allowed
+    pub fn step(&self) -> f64 {
+        self.0.step
+    }
+}
+
 impl Default for Range {
     fn default() -> Self {
         Range(uhd_sys::uhd_range_t {
@@ -51,6 +74,18 @@ impl MetaRange {
         Ok(step)
     }
 
+    /// Returns the value in this meta-range that is closest to the provided value
+    ///
+    /// If clip_step is true, the returned value is also rounded to a multiple of the step of
+    /// the range that contains it.
+    pub fn clip(&self, value: f64, clip_step: bool) -> Result<f64, Error> {
+        let mut clipped = 0.0;
+        check_status(unsafe {
+            uhd_sys::uhd_meta_range_clip(self.0, value, clip_step, &mut clipped)
+        })?;
+        Ok(clipped)
+    }
+
     /// Returns the number of ranges in this meta-range
     pub fn len(&self) -> usize {
         let mut length = 0usize;
@@ -74,6 +109,7 @@ impl MetaRange {
             },
         }
     }
+
     /// Appends a range to the end of this meta-range
     pub fn push(&mut self, range: Range) {
         check_status(unsafe { uhd_sys::uhd_meta_range_push_back(self.0, &range.0) }).unwrap();
@@ -167,9 +203,9 @@ mod fmt {
     impl Debug for Range {
         fn fmt(&self, f: &mut Formatter<'_>) -> Result {
             f.debug_struct("Range")
-                .field("start", &self.0.start)
-                .field("stop", &self.0.stop)
-                .field("step", &self.0.step)
+                .field("start", &self.start())
+                .field("stop", &self.stop())
+                .field("step", &self.step())
                 .finish()
         }
     }
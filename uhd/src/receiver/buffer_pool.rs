@@ -0,0 +1,186 @@
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+
+/// A set of equal-length sample buffers that are reused instead of being freed
+///
+/// Buffers from BufferPool::get() return to the pool when they are dropped. When the pool is
+/// empty, get() allocates a new buffer. When the pool is full, dropped buffers are freed.
+pub struct BufferPool<I> {
+    /// Buffers that are not in use, and the allocation count
+    inner: Arc<Mutex<PoolInner<I>>>,
+    /// The length of each buffer, in samples
+    buffer_length: usize,
+}
+
+struct PoolInner<I> {
+    /// Buffers that are not in use
+    free: Vec<Vec<I>>,
+    /// The maximum number of free buffers to keep
+    capacity: usize,
+    /// The number of buffers that have been allocated
+    allocations: usize,
+}
+
+impl<I> BufferPool<I>
+where
+    I: Clone + Default,
+{
+    /// Creates a pool with capacity buffers of buffer_length samples each
+    ///
+    /// All the buffers are allocated immediately.
+    pub fn new(capacity: usize, buffer_length: usize) -> Self {
+        let free = (0..capacity)
+            .map(|_| vec![I::default(); buffer_length])
+            .collect();
+        BufferPool {
+            inner: Arc::new(Mutex::new(PoolInner {
+                free,
+                capacity,
+                allocations: capacity,
+            })),
+            buffer_length,
+        }
+    }
+
+    /// Takes a buffer from the pool, or allocates a new buffer if the pool is empty
+    ///
+    /// The returned buffer contains buffer_length() samples.
+    pub fn get(&self) -> PooledBuffer<I> {
+        let mut inner = self.inner.lock().unwrap();
+        let buffer = match inner.free.pop() {
+            Some(buffer) => buffer,
+            None => {
+                inner.allocations += 1;
+                vec![I::default(); self.buffer_length]
+            }
+        };
+        PooledBuffer {
+            buffer,
+            length: self.buffer_length,
+            pool: Arc::clone(&self.inner),
+        }
+    }
+
+    /// Returns the length of each buffer, in samples
+    pub fn buffer_length(&self) -> usize {
+        self.buffer_length
+    }
+
+    /// Returns the total number of buffers that this pool has allocated, including the buffers
+    /// allocated when it was created
+    pub fn allocations(&self) -> usize {
+        self.inner.lock().unwrap().allocations
+    }
+}
+
+/// A buffer that returns to its BufferPool when dropped
+///
+/// A PooledBuffer dereferences to a slice of the valid samples in the buffer.
+pub struct PooledBuffer<I> {
+    /// The full-length buffer
+    buffer: Vec<I>,
+    /// The number of valid samples at the beginning of the buffer
+    length: usize,
+    /// The pool that this buffer returns to
+    pool: Arc<Mutex<PoolInner<I>>>,
+}
+
+impl<I> PooledBuffer<I> {
+    /// Returns the entire buffer, including samples after the valid length
+    pub(crate) fn storage_mut(&mut self) -> &mut [I] {
+        &mut self.buffer
+    }
+
+    /// Sets the number of valid samples at the beginning of the buffer
+    ///
+    /// Panics if length is greater than the length of the buffer.
+    pub(crate) fn set_len(&mut self, length: usize) {
+        assert!(length <= self.buffer.len(), "Length larger than buffer");
+        self.length = length;
+    }
+}
+
+impl<I> Deref for PooledBuffer<I> {
+    type Target = [I];
+
+    fn deref(&self) -> &Self::Target {
+        &self.buffer[..self.length]
+    }
+}
+
+impl<I> DerefMut for PooledBuffer<I> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buffer[..self.length]
+    }
+}
+
+impl<I> Drop for PooledBuffer<I> {
+    fn drop(&mut self) {
+        if let Ok(mut pool) = self.pool.lock() {
+            if pool.free.len() < pool.capacity {
+                let buffer = mem::take(&mut self.buffer);
+                pool.free.push(buffer);
+            }
+        }
+    }
+}
+
+mod fmt {
+    use super::{BufferPool, PooledBuffer};
+    use std::fmt::{Debug, Formatter, Result};
+
+    impl<I> Debug for BufferPool<I> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            f.debug_struct("BufferPool")
+                .field("buffer_length", &self.buffer_length)
+                .finish()
+        }
+    }
+
+    impl<I: Debug> Debug for PooledBuffer<I> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            f.debug_list().entries(self.iter()).finish()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BufferPool;
+
+    #[test]
+    fn steady_state_does_not_allocate() {
+        let pool = BufferPool::<i16>::new(2, 1024);
+        assert_eq!(pool.allocations(), 2);
+        for _ in 0..100 {
+            let first = pool.get();
+            let second = pool.get();
+            assert_eq!(first.len(), 1024);
+            drop(first);
+            drop(second);
+        }
+        assert_eq!(pool.allocations(), 2);
+    }
+
+    #[test]
+    fn empty_pool_allocates() {
+        let pool = BufferPool::<i16>::new(1, 16);
+        let buffers: Vec<_> = (0..3).map(|_| pool.get()).collect();
+        assert_eq!(pool.allocations(), 3);
+        drop(buffers);
+        // Only one buffer fits back in the pool
+        let _first = pool.get();
+        let _second = pool.get();
+        assert_eq!(pool.allocations(), 4);
+    }
+
+    #[test]
+    fn valid_length() {
+        let pool = BufferPool::<i16>::new(1, 16);
+        let mut buffer = pool.get();
+        buffer.storage_mut()[3] = 7;
+        buffer.set_len(4);
+        assert_eq!(&*buffer, &[0, 0, 0, 7]);
+    }
+}
@@ -0,0 +1,95 @@
+use std::mem;
+
+use crate::ReceiveMetadata;
+
+/// Collects received samples into bursts, using the start of burst and end of burst flags
+/// in the receive metadata
+///
+/// Samples that arrive outside a burst (after an end of burst and before the next start of
+/// burst) are discarded. If a start of burst arrives before the previous burst has ended, the
+/// incomplete burst is discarded.
+#[derive(Debug, Clone, Default)]
+pub struct BurstAccumulator<I> {
+    /// Samples of the burst in progress
+    samples: Vec<I>,
+    /// True if a burst has started and has not ended
+    in_burst: bool,
+}
+
+impl<I> BurstAccumulator<I>
+where
+    I: Clone,
+{
+    /// Creates an accumulator with no burst in progress
+    pub fn new() -> Self {
+        BurstAccumulator {
+            samples: Vec::new(),
+            in_burst: false,
+        }
+    }
+
+    /// Adds samples from a receive operation, using the burst flags from its metadata
+    ///
+    /// samples should be the part of the receive buffer that contains received samples.
+    /// If these samples complete a burst, this function returns all samples of the burst.
+    pub fn push_received(&mut self, samples: &[I], metadata: &ReceiveMetadata) -> Option<Vec<I>> {
+        self.push(samples, metadata.start_of_burst(), metadata.end_of_burst())
+    }
+
+    /// Adds samples with the provided burst flags
+    ///
+    /// If these samples complete a burst, this function returns all samples of the burst.
+    pub fn push(
+        &mut self,
+        samples: &[I],
+        start_of_burst: bool,
+        end_of_burst: bool,
+    ) -> Option<Vec<I>> {
+        if start_of_burst {
+            self.samples.clear();
+            self.in_burst = true;
+        }
+        if !self.in_burst {
+            return None;
+        }
+        self.samples.extend_from_slice(samples);
+        if end_of_burst {
+            self.in_burst = false;
+            Some(mem::take(&mut self.samples))
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if a burst has started and has not ended
+    pub fn in_burst(&self) -> bool {
+        self.in_burst
+    }
+
+    /// Returns the number of samples in the burst in progress
+    pub fn pending(&self) -> usize {
+        self.samples.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BurstAccumulator;
+
+    #[test]
+    fn segments_bursts() {
+        let mut bursts = BurstAccumulator::new();
+        // Outside a burst
+        assert_eq!(bursts.push(&[1, 2], false, false), None);
+        assert_eq!(bursts.push(&[3, 4], true, false), None);
+        assert_eq!(bursts.pending(), 2);
+        assert_eq!(bursts.push(&[5], false, true), Some(vec![3, 4, 5]));
+        assert!(!bursts.in_burst());
+        // A burst in one packet
+        assert_eq!(bursts.push(&[6], true, true), Some(vec![6]));
+        // An incomplete burst is replaced by the next one
+        assert_eq!(bursts.push(&[7, 8], true, false), None);
+        assert_eq!(bursts.push(&[9], true, false), None);
+        assert_eq!(bursts.push(&[10], false, true), Some(vec![9, 10]));
+    }
+}
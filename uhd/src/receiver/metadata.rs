@@ -7,7 +7,7 @@ use crate::TimeSpec;
 
 /// Data about a receive operation
 pub struct ReceiveMetadata {
-    /// Handle to C++ object
+    /// Handle to C++ object, or null for metadata that was created without UHD
     handle: uhd_sys::uhd_rx_metadata_handle,
     /// Number of samples received
     samples: usize,
@@ -18,8 +18,22 @@ impl ReceiveMetadata {
         Default::default()
     }
 
-    /// Returns the timestamp of (the first?) of the received samples, according to the USRP's
-    /// internal clock
+    /// Creates metadata with the provided number of samples without using UHD (for the mock
+    /// streamers only)
+    ///
+    /// The metadata has no time, no flags, and no error.
+    #[cfg(feature = "mock")]
+    pub(crate) fn without_handle(samples: usize) -> Self {
+        ReceiveMetadata {
+            handle: ptr::null_mut(),
+            samples,
+        }
+    }
+
+    /// Returns the timestamp of the first received sample in the buffers, according to the
+    /// USRP's internal clock
+    ///
+    /// Timestamps of later samples can be calculated from this time and the sample rate.
     pub fn time_spec(&self) -> Option<TimeSpec> {
         if self.has_time_spec() {
             let mut time = TimeSpec::default();
@@ -43,6 +57,9 @@ impl ReceiveMetadata {
 
     /// Returns true if this metadata object has a time
     fn has_time_spec(&self) -> bool {
+        if self.handle.is_null() {
+            return false;
+        }
         let mut has = false;
         check_status(unsafe { uhd_sys::uhd_rx_metadata_has_time_spec(self.handle, &mut has) })
             .unwrap();
@@ -51,6 +68,9 @@ impl ReceiveMetadata {
 
     /// Returns true if the received samples are at the beginning of a burst
     pub fn start_of_burst(&self) -> bool {
+        if self.handle.is_null() {
+            return false;
+        }
         let mut value = false;
         check_status(unsafe { uhd_sys::uhd_rx_metadata_start_of_burst(self.handle, &mut value) })
             .unwrap();
@@ -59,6 +79,9 @@ impl ReceiveMetadata {
 
     /// Returns true if the received samples are at the end of a burst
     pub fn end_of_burst(&self) -> bool {
+        if self.handle.is_null() {
+            return false;
+        }
         let mut value = false;
         check_status(unsafe { uhd_sys::uhd_rx_metadata_end_of_burst(self.handle, &mut value) })
             .unwrap();
@@ -70,6 +93,9 @@ impl ReceiveMetadata {
     /// If this is the case, the fragment_offset() function returns the offset from the beginning
     /// of the packet to the first sample received
     pub fn more_fragments(&self) -> bool {
+        if self.handle.is_null() {
+            return false;
+        }
         let mut value = false;
         check_status(unsafe { uhd_sys::uhd_rx_metadata_more_fragments(self.handle, &mut value) })
             .unwrap();
@@ -79,6 +105,9 @@ impl ReceiveMetadata {
     /// If more_fragments() returned true, this function returns the offset from the beginning
     /// of the packet to the first sample received
     pub fn fragment_offset(&self) -> usize {
+        if self.handle.is_null() {
+            return 0;
+        }
         let mut value = 0usize;
         check_status(unsafe {
             uhd_sys::uhd_rx_metadata_fragment_offset(
@@ -92,6 +121,9 @@ impl ReceiveMetadata {
 
     /// Returns true if a packet was dropped or received out of order
     pub fn out_of_sequence(&self) -> bool {
+        if self.handle.is_null() {
+            return false;
+        }
         let mut value = false;
         check_status(unsafe { uhd_sys::uhd_rx_metadata_out_of_sequence(self.handle, &mut value) })
             .unwrap();
@@ -111,6 +143,9 @@ impl ReceiveMetadata {
     /// Returns the error code associated with the receive operation
     fn error_code(&self) -> uhd_sys::uhd_rx_metadata_error_code_t::Type {
         let mut code = uhd_sys::uhd_rx_metadata_error_code_t::UHD_RX_METADATA_ERROR_CODE_NONE;
+        if self.handle.is_null() {
+            return code;
+        }
         check_status(unsafe { uhd_sys::uhd_rx_metadata_error_code(self.handle, &mut code) })
             .unwrap();
         code
@@ -166,6 +201,9 @@ impl Default for ReceiveMetadata {
 
 impl Drop for ReceiveMetadata {
     fn drop(&mut self) {
+        if self.handle.is_null() {
+            return;
+        }
         let _ = unsafe { uhd_sys::uhd_rx_metadata_free(&mut self.handle) };
     }
 }
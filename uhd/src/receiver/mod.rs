@@ -1,3 +1,5 @@
+pub mod buffer_pool;
+pub mod burst;
 pub mod error;
 pub mod info;
 pub mod metadata;
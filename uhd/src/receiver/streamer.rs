@@ -1,15 +1,25 @@
+use std::io::Write;
 use std::marker::PhantomData;
 use std::os::raw::c_void;
 use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
+use super::buffer_pool::{BufferPool, PooledBuffer};
+use super::error::ReceiveErrorKind;
 use crate::{
     error::{check_status, Error, Result},
-    stream::StreamCommand,
+    sample_file::FileSample,
+    stream::{StreamCommand, StreamCommandType, StreamTime, Streamer},
     usrp::Usrp,
-    utils::check_equal_buffer_lengths,
-    ReceiveMetadata,
+    utils::{c_buffer_length, check_equal_buffer_lengths, packet_timeout},
+    ReceiveMetadata, TimeSpec,
 };
 
+/// The longest time that the functions that stop streaming spend discarding the samples that
+/// arrive afterwards
+const FLUSH_LIMIT: Duration = Duration::from_secs(1);
+
 /// A streamer used to receive samples from a USRP
 ///
 /// The type parameter I is the type of sample that this streamer receives.
@@ -23,20 +33,37 @@ pub struct ReceiveStreamer<'usrp, I> {
     /// Invariant: If this is not empty, its length is equal to the value returned by
     /// self.num_channels().
     buffer_pointers: Vec<*mut c_void>,
+    /// Indexes of the channels that this streamer was created with
+    channels: Vec<usize>,
+    /// The receive sample rate when this streamer was created, in samples per second
+    rate: f64,
+    /// The value from max_num_samps(), or 0 if it has not been queried yet
+    max_num_samps: AtomicUsize,
     /// Link to the USRP that this streamer is associated with
     usrp: PhantomData<&'usrp Usrp>,
     /// Item type phantom data
     item_phantom: PhantomData<I>,
 }
 
-impl<I> ReceiveStreamer<'_, I> {
+impl<'usrp, I> ReceiveStreamer<'usrp, I> {
     /// Creates a receive streamer with a null streamer handle (for internal use only)
     ///
     /// After creating a streamer with this function, its streamer handle must be initialized.
-    pub(crate) fn new() -> Self {
+    ///
+    /// channels: The channel indexes from the stream arguments. An empty list means channel 0.
+    ///
+    /// rate: The current receive sample rate
+    pub(crate) fn new(channels: &[usize], rate: f64) -> Self {
         ReceiveStreamer {
             handle: ptr::null_mut(),
             buffer_pointers: Vec::new(),
+            channels: if channels.is_empty() {
+                vec![0]
+            } else {
+                channels.to_vec()
+            },
+            rate,
+            max_num_samps: AtomicUsize::new(0),
             usrp: PhantomData,
             item_phantom: PhantomData,
         }
@@ -53,12 +80,90 @@ impl<I> ReceiveStreamer<'_, I> {
 
     /// Sends a stream command to the USRP
     ///
-    /// This can be used to start or stop streaming
+    /// This can be used to start or stop streaming. UHD applies a stream command to all
+    /// channels of the streamer, and cannot start or stop only some of them. To start channels
+    /// at different times, create one streamer for each channel with
+    /// Usrp::get_rx_streams_per_channel() and send a timed command to each streamer.
     pub fn send_command(&mut self, command: &StreamCommand) -> Result<(), Error> {
         let command_c = command.as_c_command();
         check_status(unsafe { uhd_sys::uhd_rx_streamer_issue_stream_cmd(self.handle, &command_c) })
     }
 
+    /// Sends a command to stop continuous streaming when the USRP's clock reaches a time
+    ///
+    /// Unlike a stop command with StreamTime::Now, the last sample received is the sample just
+    /// before the provided time, so the number of samples captured is known in advance.
+    ///
+    /// This function returns Error::InvalidTimeSpec if the time is negative or the fraction is
+    /// not in the range [0, 1).
+    pub fn stop_at(&mut self, time: &TimeSpec) -> Result<(), Error> {
+        self.send_command(&StreamCommand {
+            time: StreamTime::Later(time.to_duration()?),
+            command_type: StreamCommandType::StopContinuous,
+        })
+    }
+
+    /// Returns the underlying UHD streamer handle, for calling UHD functions that this library
+    /// does not wrap
+    ///
+    /// # Safety
+    ///
+    /// The handle must not be freed, and must not be used after this streamer is dropped. Calls
+    /// through the handle must follow the same threading rules as the functions of this streamer.
+    #[cfg(feature = "raw-handle")]
+    pub unsafe fn raw_handle(&self) -> uhd_sys::uhd_rx_streamer_handle {
+        self.handle
+    }
+
+    /// Returns the indexes of the channels that this streamer is associated with
+    pub fn channels(&self) -> &[usize] {
+        &self.channels
+    }
+
+    /// Returns the receive sample rate when this streamer was created, in samples per second
+    ///
+    /// This can be used to calculate the duration of a block of received samples. If the
+    /// sample rate is changed after this streamer is created, this function still returns
+    /// the old rate.
+    pub fn configured_rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Returns a timeout, in seconds, for receiving one packet at configured_rate()
+    ///
+    /// This is twice the time that one packet of max_num_samps() samples takes at the sample
+    /// rate, but at least 10 milliseconds to allow for host scheduling delays. A fixed timeout
+    /// such as 0.1 seconds is longer than needed at high sample rates and can be too short at
+    /// very low sample rates.
+    ///
+    /// The first receive operation after streaming starts can take longer, so it should use a
+    /// longer timeout.
+    pub fn default_timeout(&self) -> Result<f64> {
+        Ok(packet_timeout(self.max_num_samps()?, self.rate))
+    }
+
+    /// Returns the maximum number of samples per channel in one packet
+    ///
+    /// With one_packet enabled, receive() returns at most this many samples. Receiving in
+    /// chunks of this size usually gives the best throughput.
+    ///
+    /// The value is queried from UHD on the first successful call and cached.
+    pub fn max_num_samps(&self) -> Result<usize> {
+        let cached = self.max_num_samps.load(Ordering::Relaxed);
+        if cached != 0 {
+            return Ok(cached);
+        }
+        let mut samples = 0usize;
+        check_status(unsafe {
+            uhd_sys::uhd_rx_streamer_max_num_samps(
+                self.handle,
+                &mut samples as *mut usize as *mut _,
+            )
+        })?;
+        self.max_num_samps.store(samples, Ordering::Relaxed);
+        Ok(samples)
+    }
+
     /// Returns the number of channels that this streamer is associated with
     pub fn num_channels(&self) -> usize {
         let mut num_channels = 0usize;
@@ -78,10 +183,22 @@ impl<I> ReceiveStreamer<'_, I> {
     /// buffers should have the same length. This function will panic if the number of buffers is
     /// not equal to self.num_channels(), or if not all buffers have the same length.
     ///
-    /// timeout: The timeout for the receive operation, in seconds
+    /// UHD always streams every channel that the streamer was created with, so a channel
+    /// cannot be disabled by passing an empty buffer for it. To ignore a channel without
+    /// creating a new streamer, pass a scratch buffer of the same length and discard its
+    /// contents.
+    ///
+    /// timeout: The timeout for the receive operation, in seconds. There is one timeout for
+    /// the whole call, not one for each channel. UHD returns the same number of samples on every
+    /// channel, so if one channel has not received enough samples before the timeout, this
+    /// function returns fewer samples on all channels.
     ///
     /// one_packet: If this is true, one call to receive() will not copy samples from more than
-    /// one packet of the underlying protocol
+    /// one packet of the underlying protocol. It then returns at most max_num_samps() samples,
+    /// as soon as one packet has arrived, even if the buffers have space for more. This keeps
+    /// the latency and the amount of copying in each call bounded, which is useful when each
+    /// packet is processed as soon as it arrives. If this is false, receive() waits (up to the
+    /// timeout) until the buffers are full.
     ///
     /// On success, this function returns a ReceiveMetadata object with information about
     /// the number of samples actually received.
@@ -103,10 +220,12 @@ impl<I> ReceiveStreamer<'_, I> {
         assert_eq!(
             buffers.len(),
             self.buffer_pointers.len(),
-            "Number of buffers is not equal to this streamer's number of channels"
+            "Streamer configured for channels {:?} but got {} buffer(s)",
+            self.channels,
+            buffers.len()
         );
         // Check that all buffers have the same length
-        let buffer_length = check_equal_buffer_lengths(buffers);
+        let buffer_length = c_buffer_length(check_equal_buffer_lengths(buffers))?;
 
         // Copy buffer pointers into C-compatible form
         for (entry, buffer) in self.buffer_pointers.iter_mut().zip(buffers.iter_mut()) {
@@ -117,7 +236,7 @@ impl<I> ReceiveStreamer<'_, I> {
             uhd_sys::uhd_rx_streamer_recv(
                 self.handle,
                 self.buffer_pointers.as_mut_ptr(),
-                buffer_length as _,
+                buffer_length,
                 metadata.handle_mut(),
                 timeout,
                 one_packet,
@@ -133,6 +252,327 @@ impl<I> ReceiveStreamer<'_, I> {
     pub fn receive_simple(&mut self, buffer: &mut [I]) -> Result<ReceiveMetadata> {
         self.receive(&mut [buffer], 0.1, false)
     }
+
+    /// Receives samples if any are available, without waiting
+    ///
+    /// The buffers are the same as for receive(). This function returns None if no samples
+    /// were available. Other errors are reported in the returned metadata, as with receive().
+    pub fn try_receive(&mut self, buffers: &mut [&mut [I]]) -> Result<Option<ReceiveMetadata>> {
+        let metadata = self.receive(buffers, 0.0, false)?;
+        let timed_out = matches!(
+            metadata.last_error().map(|error| error.kind()),
+            Some(ReceiveErrorKind::Timeout)
+        );
+        if metadata.samples() == 0 && timed_out {
+            Ok(None)
+        } else {
+            Ok(Some(metadata))
+        }
+    }
+
+    /// Receives and discards samples until no samples arrive within the timeout, or until
+    /// limit has passed
+    ///
+    /// Streaming should be stopped before flushing. Otherwise samples keep arriving, and this
+    /// function only returns when limit has passed. Overflows and other receive errors do not
+    /// end the flush. It returns the number of samples discarded on each channel.
+    pub fn flush(&mut self, timeout: f64, limit: Duration) -> Result<usize>
+    where
+        I: Clone + Default,
+    {
+        const FLUSH_BUFFER_LENGTH: usize = 4096;
+        let deadline = Instant::now() + limit;
+        let mut storage = vec![vec![I::default(); FLUSH_BUFFER_LENGTH]; self.num_channels()];
+        let mut flushed = 0;
+        while Instant::now() < deadline {
+            let mut buffers: Vec<&mut [I]> =
+                storage.iter_mut().map(|buffer| &mut buffer[..]).collect();
+            let metadata = self.receive(&mut buffers, timeout, false)?;
+            flushed += metadata.samples();
+            let timed_out = matches!(
+                metadata.last_error().map(|error| error.kind()),
+                Some(ReceiveErrorKind::Timeout)
+            );
+            if timed_out {
+                break;
+            }
+        }
+        Ok(flushed)
+    }
+
+    /// Receives a number of samples on a single channel and writes them to a writer
+    ///
+    /// This function starts continuous streaming, receives count samples, stops streaming, and
+    /// discards any samples that arrive after the stop. The samples are written in the layout
+    /// that FileSample describes, and the writer is flushed at the end.
+    ///
+    /// If the USRP reports an overflow, samples were lost between two of the samples written.
+    /// The capture continues, and the number of overflows is returned. Other receive errors
+    /// end the capture (after trying to stop streaming). The writer is then flushed, and this
+    /// function returns Error::PartialCapture with the number of samples that the writer holds.
+    ///
+    /// This function panics if this streamer has more than one channel.
+    pub fn write_samples_to<W>(&mut self, writer: &mut W, count: usize) -> Result<usize>
+    where
+        I: FileSample + Clone + Default,
+        W: Write,
+    {
+        self.send_command(&StreamCommand {
+            time: StreamTime::Now,
+            command_type: StreamCommandType::StartContinuous,
+        })?;
+        let mut written = 0;
+        let result = self.receive_to_writer(writer, count, &mut written);
+        // Stop even if the capture failed
+        let stop_result = self.send_command(&StreamCommand {
+            time: StreamTime::Now,
+            command_type: StreamCommandType::StopContinuous,
+        });
+        let overflows = match result {
+            Ok(overflows) => overflows,
+            Err(error) => {
+                // Keep the samples that were written before the failure
+                writer.flush()?;
+                return Err(Error::PartialCapture {
+                    written,
+                    source: Box::new(error),
+                });
+            }
+        };
+        stop_result?;
+        self.flush(0.1, FLUSH_LIMIT)?;
+        writer.flush()?;
+        Ok(overflows)
+    }
+
+    /// Receives count samples and writes them to a writer, and returns the number of overflows
+    /// (for use by write_samples_to() only)
+    ///
+    /// written is updated with the number of samples written, so that it is correct if this
+    /// function returns an error.
+    fn receive_to_writer<W>(
+        &mut self,
+        writer: &mut W,
+        count: usize,
+        written: &mut usize,
+    ) -> Result<usize>
+    where
+        I: FileSample + Clone + Default,
+        W: Write,
+    {
+        // Long enough for the first samples to arrive after streaming starts
+        const TIMEOUT: f64 = 1.0;
+        let chunk = self.max_num_samps()?;
+        let mut buffer = vec![I::default(); chunk];
+        let mut bytes = vec![0u8; chunk * I::SIZE];
+        let mut overflows = 0;
+        while *written < count {
+            let length = chunk.min(count - *written);
+            let metadata = self.receive(&mut [&mut buffer[..length]], TIMEOUT, false)?;
+            if let Some(error) = metadata.last_error() {
+                match error.kind() {
+                    ReceiveErrorKind::Overflow | ReceiveErrorKind::OutOfSequence => overflows += 1,
+                    ReceiveErrorKind::Timeout => return Err(Error::Timeout),
+                    ReceiveErrorKind::LateCommand => return Err(Error::LateCommand),
+                    _ => return Err(Error::Other(error.into())),
+                }
+            }
+            let samples = &buffer[..metadata.samples()];
+            for (sample, sample_bytes) in samples.iter().zip(bytes.chunks_exact_mut(I::SIZE)) {
+                sample.write_bytes(sample_bytes);
+            }
+            writer.write_all(&bytes[..samples.len() * I::SIZE])?;
+            *written += samples.len();
+        }
+        Ok(overflows)
+    }
+
+    /// Starts streaming, receives and discards samples for a duration, and then stops
+    /// streaming
+    ///
+    /// This can be used to let automatic gain control, DC offset correction, and other
+    /// front-end settings settle before a capture. The duration is measured in samples at
+    /// configured_rate(), not in wall-clock time.
+    ///
+    /// timeout: The timeout for each receive operation, in seconds. The first samples
+    /// can take several hundred milliseconds to arrive after streaming starts.
+    ///
+    /// The returned statistics count samples on each channel and overflows (including
+    /// out-of-sequence packets). If no samples arrive before the timeout, this function returns
+    /// Error::Timeout. Streaming is stopped even if an error occurs.
+    pub fn run_discard(&mut self, duration: Duration, timeout: f64) -> Result<DiscardStats>
+    where
+        I: Clone + Default,
+    {
+        let count = (duration.as_secs_f64() * self.rate).round() as usize;
+        self.send_command(&StreamCommand {
+            time: StreamTime::Now,
+            command_type: StreamCommandType::StartContinuous,
+        })?;
+        let result = self.receive_discard(count, timeout);
+        // Stop even if receiving failed
+        let stop_result = self.send_command(&StreamCommand {
+            time: StreamTime::Now,
+            command_type: StreamCommandType::StopContinuous,
+        });
+        let stats = result?;
+        stop_result?;
+        self.flush(0.1, FLUSH_LIMIT)?;
+        Ok(stats)
+    }
+
+    /// Receives and discards count samples on each channel (for use by run_discard() only)
+    fn receive_discard(&mut self, count: usize, timeout: f64) -> Result<DiscardStats>
+    where
+        I: Clone + Default,
+    {
+        let chunk = self.max_num_samps()?;
+        let mut buffers = vec![vec![I::default(); chunk]; self.num_channels()];
+        let mut stats = DiscardStats::default();
+        while stats.samples < count {
+            let length = chunk.min(count - stats.samples);
+            let mut buffer_slices: Vec<&mut [I]> = buffers
+                .iter_mut()
+                .map(|buffer| &mut buffer[..length])
+                .collect();
+            let metadata = self.receive(&mut buffer_slices, timeout, false)?;
+            if let Some(error) = metadata.last_error() {
+                match error.kind() {
+                    ReceiveErrorKind::Overflow | ReceiveErrorKind::OutOfSequence => {
+                        stats.overflows += 1
+                    }
+                    ReceiveErrorKind::Timeout => return Err(Error::Timeout),
+                    ReceiveErrorKind::LateCommand => return Err(Error::LateCommand),
+                    _ => return Err(Error::Other(error.into())),
+                }
+            }
+            stats.samples += metadata.samples();
+        }
+        Ok(stats)
+    }
+
+    /// Returns an iterator that receives chunks of samples on a single channel into buffers
+    /// from a pool
+    ///
+    /// pool_size: The number of buffers to keep for reuse. This should be at least the number
+    /// of chunks that the caller holds at the same time.
+    ///
+    /// chunk: The length of each buffer, in samples, or None to use max_num_samps()
+    ///
+    /// timeout: The timeout for each receive operation, in seconds
+    ///
+    /// Each item contains a buffer with the received samples and the metadata from the receive
+    /// operation. When a buffer is dropped, it returns to the pool, so a sustained capture does
+    /// not allocate a new buffer for each chunk. Streaming must be started with a stream
+    /// command before using the iterator. The iterator ends after a chunk with the end of
+    /// burst flag, or when a receive operation times out without receiving any samples. It
+    /// yields an error if a receive operation fails.
+    ///
+    /// This function panics if this streamer has more than one channel.
+    pub fn samples_pooled(
+        &mut self,
+        pool_size: usize,
+        chunk: Option<usize>,
+        timeout: f64,
+    ) -> Result<PooledSamples<'_, 'usrp, I>>
+    where
+        I: Clone + Default,
+    {
+        assert_eq!(
+            self.channels.len(),
+            1,
+            "samples_pooled() needs a single-channel streamer, but the streamer has channels {:?}",
+            self.channels
+        );
+        let chunk = match chunk {
+            Some(chunk) => chunk,
+            None => self.max_num_samps()?,
+        };
+        Ok(PooledSamples {
+            streamer: self,
+            pool: BufferPool::new(pool_size, chunk),
+            timeout,
+            done: false,
+        })
+    }
+}
+
+/// Statistics from ReceiveStreamer::run_discard()
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiscardStats {
+    /// The number of samples received and discarded on each channel
+    pub samples: usize,
+    /// The number of overflows and out-of-sequence packets
+    ///
+    /// This should be zero on a healthy link.
+    pub overflows: usize,
+}
+
+/// An iterator that receives samples into pooled buffers
+///
+/// This is returned by ReceiveStreamer::samples_pooled().
+pub struct PooledSamples<'s, 'usrp, I> {
+    streamer: &'s mut ReceiveStreamer<'usrp, I>,
+    pool: BufferPool<I>,
+    /// The timeout for each receive operation, in seconds
+    timeout: f64,
+    /// True if the iterator has ended
+    done: bool,
+}
+
+impl<I> PooledSamples<'_, '_, I> {
+    /// Returns the pool that the buffers come from
+    pub fn pool(&self) -> &BufferPool<I> {
+        &self.pool
+    }
+}
+
+impl<I> Iterator for PooledSamples<'_, '_, I>
+where
+    I: Clone + Default,
+{
+    type Item = Result<(PooledBuffer<I>, ReceiveMetadata)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut buffer = self.pool.get();
+        let metadata = match self
+            .streamer
+            .receive(&mut [buffer.storage_mut()], self.timeout, false)
+        {
+            Ok(metadata) => metadata,
+            Err(e) => return Some(Err(e)),
+        };
+        let timed_out = matches!(
+            metadata.last_error().map(|error| error.kind()),
+            Some(ReceiveErrorKind::Timeout)
+        );
+        if metadata.samples() == 0 && timed_out {
+            self.done = true;
+            return None;
+        }
+        self.done = metadata.end_of_burst();
+        buffer.set_len(metadata.samples());
+        Some(Ok((buffer, metadata)))
+    }
+}
+
+impl<I> Streamer for ReceiveStreamer<'_, I> {
+    type Item = I;
+
+    fn channels(&self) -> &[usize] {
+        ReceiveStreamer::channels(self)
+    }
+
+    fn num_channels(&self) -> usize {
+        ReceiveStreamer::num_channels(self)
+    }
+
+    fn send_command(&mut self, command: &StreamCommand) -> Result<(), Error> {
+        ReceiveStreamer::send_command(self, command)
+    }
 }
 
 impl<I> Drop for ReceiveStreamer<'_, I> {
@@ -0,0 +1,68 @@
+//!
+//! Conversion of samples to and from a binary file layout
+//!
+
+use num_complex::Complex;
+
+/// A sample type that can be stored in a sample file
+///
+/// In a sample file, each sample is stored as its in-phase (real) part followed by its
+/// quadrature (imaginary) part, with no header and no padding. Each part is stored in
+/// little-endian byte order with the size of the sample type (for example, two 2-byte
+/// integers for `Complex<i16>`). On little-endian hosts, this is the same layout that the UHD
+/// `rx_samples_to_file` example writes.
+pub trait FileSample: Sized {
+    /// The number of bytes that one sample uses in a file
+    const SIZE: usize;
+
+    /// Writes this sample into bytes, which must have a length of SIZE
+    fn write_bytes(&self, bytes: &mut [u8]);
+
+    /// Reads a sample from bytes, which must have a length of SIZE
+    fn read_bytes(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_file_sample {
+    ($($part:ty),*) => {
+        $(
+            impl FileSample for Complex<$part> {
+                const SIZE: usize = 2 * std::mem::size_of::<$part>();
+
+                fn write_bytes(&self, bytes: &mut [u8]) {
+                    let (re, im) = bytes.split_at_mut(Self::SIZE / 2);
+                    re.copy_from_slice(&self.re.to_le_bytes());
+                    im.copy_from_slice(&self.im.to_le_bytes());
+                }
+
+                fn read_bytes(bytes: &[u8]) -> Self {
+                    let mut re = [0u8; std::mem::size_of::<$part>()];
+                    let mut im = [0u8; std::mem::size_of::<$part>()];
+                    re.copy_from_slice(&bytes[..Self::SIZE / 2]);
+                    im.copy_from_slice(&bytes[Self::SIZE / 2..]);
+                    Complex::new(<$part>::from_le_bytes(re), <$part>::from_le_bytes(im))
+                }
+            }
+        )*
+    };
+}
+
+impl_file_sample!(f64, f32, i16, i8);
+
+#[cfg(test)]
+mod test {
+    use super::FileSample;
+    use num_complex::{Complex, Complex32};
+
+    #[test]
+    fn interleaved_little_endian() {
+        let mut bytes = [0u8; 4];
+        Complex::<i16>::new(0x0102, -2).write_bytes(&mut bytes);
+        assert_eq!(bytes, [0x02, 0x01, 0xfe, 0xff]);
+        assert_eq!(Complex::<i16>::read_bytes(&bytes), Complex::new(0x0102, -2));
+
+        let sample = Complex32::new(0.5, -1.25);
+        let mut bytes = [0u8; Complex32::SIZE];
+        sample.write_bytes(&mut bytes);
+        assert_eq!(Complex32::read_bytes(&bytes), sample);
+    }
+}
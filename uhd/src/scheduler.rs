@@ -0,0 +1,185 @@
+//!
+//! Sequencing of timed commands by sample index
+//!
+
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use crate::{Error, TimeSpec, TuneRequest, Usrp};
+
+/// A setting change that a Scheduler applies at a sample index
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScheduledAction {
+    /// Sets the receive center frequency of a channel
+    RxTune {
+        request: TuneRequest,
+        channel: usize,
+    },
+    /// Sets the transmit center frequency of a channel
+    TxTune {
+        request: TuneRequest,
+        channel: usize,
+    },
+    /// Sets the overall receive gain of a channel, in decibels
+    RxGain { gain: f64, channel: usize },
+    /// Sets the overall transmit gain of a channel, in decibels
+    TxGain { gain: f64, channel: usize },
+}
+
+/// Converts sample indices into USRP times and applies setting changes at those times
+///
+/// Sample index 0 corresponds to the start time of the scheduler. The time of each index is
+/// calculated with TimeSpec::from_ticks(), so it does not drift for large indices.
+///
+/// Actions are queued with schedule() and sent to the USRP as timed commands by
+/// execute(). Bursts are not actions: to transmit a burst at a sample index, use time_of()
+/// as the time in the TransmitMetadata of the burst.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use uhd::{Scheduler, ScheduledAction, TuneRequest, Usrp};
+/// # use std::time::Duration;
+/// # fn main() -> Result<(), uhd::Error> {
+/// let mut usrp = Usrp::open("")?;
+/// let mut scheduler = Scheduler::starting_in(&usrp, Duration::from_secs(1), 1e6)?;
+/// // At sample 1 000 000, hop to 920 MHz
+/// scheduler.schedule(
+///     1_000_000,
+///     ScheduledAction::RxTune {
+///         request: TuneRequest::with_frequency(920e6),
+///         channel: 0,
+///     },
+/// );
+/// scheduler.execute(&mut usrp)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    /// The time of sample index 0
+    start: TimeSpec,
+    /// Sample rate, in samples per second
+    sample_rate: f64,
+    /// Actions that have not been executed, with their sample indices
+    actions: Vec<(u64, ScheduledAction)>,
+}
+
+impl Scheduler {
+    /// Creates a scheduler with sample index 0 at the provided time, according to the USRP's
+    /// clock
+    pub fn new(start: TimeSpec, sample_rate: f64) -> Self {
+        Scheduler {
+            start,
+            sample_rate,
+            actions: Vec::new(),
+        }
+    }
+
+    /// Creates a scheduler with sample index 0 at a delay after the current time of
+    /// motherboard 0
+    ///
+    /// The delay should be long enough to queue and execute all actions before the first one
+    /// is due.
+    pub fn starting_in(usrp: &Usrp, delay: Duration, sample_rate: f64) -> Result<Self, Error> {
+        let start = usrp.get_current_time(0)? + TimeSpec::from(delay);
+        Ok(Scheduler::new(start, sample_rate))
+    }
+
+    /// Returns the time of sample index 0
+    pub fn start(&self) -> &TimeSpec {
+        &self.start
+    }
+
+    /// Returns the USRP time of a sample index
+    ///
+    /// This function returns Error::SampleIndexTooLarge if the index does not fit in an i64,
+    /// and Error::InvalidTickRate if the sample rate is not finite or is less than 1 sample
+    /// per second.
+    pub fn time_of(&self, sample: u64) -> Result<TimeSpec, Error> {
+        let ticks = i64::try_from(sample).map_err(|_| Error::SampleIndexTooLarge(sample))?;
+        Ok(self.start.clone() + TimeSpec::from_ticks(ticks, self.sample_rate)?)
+    }
+
+    /// Queues an action to be applied at a sample index
+    pub fn schedule(&mut self, sample: u64, action: ScheduledAction) {
+        self.actions.push((sample, action));
+    }
+
+    /// Returns the number of actions that have not been executed
+    pub fn pending(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Sends all queued actions to the USRP as timed commands, in sample index order, and
+    /// returns the number of actions sent
+    ///
+    /// Actions with the same sample index are sent in the order they were scheduled. The
+    /// command time of all motherboards is cleared afterwards, even if an action fails.
+    ///
+    /// If the earliest action is not after the current time of motherboard 0, this function
+    /// returns Error::LateCommand and sends no actions. The actions stay queued.
+    pub fn execute(&mut self, usrp: &mut Usrp) -> Result<usize, Error> {
+        self.actions.sort_by_key(|(sample, _)| *sample);
+        if let Some((first, _)) = self.actions.first() {
+            if self.time_of(*first)? <= usrp.get_current_time(0)? {
+                return Err(Error::LateCommand);
+            }
+        }
+
+        let actions = std::mem::take(&mut self.actions);
+        let count = actions.len();
+        let result = actions
+            .into_iter()
+            .try_for_each(|(sample, action)| self.apply(usrp, sample, &action));
+        let clear_result = usrp.clear_command_time(Usrp::ALL_MBOARDS);
+        result?;
+        clear_result?;
+        Ok(count)
+    }
+
+    /// Sets the command time for a sample index and applies one action
+    fn apply(&self, usrp: &mut Usrp, sample: u64, action: &ScheduledAction) -> Result<(), Error> {
+        usrp.set_command_time(&self.time_of(sample)?, Usrp::ALL_MBOARDS)?;
+        match action {
+            ScheduledAction::RxTune { request, channel } => {
+                usrp.set_rx_frequency(request, *channel).map(drop)
+            }
+            ScheduledAction::TxTune { request, channel } => {
+                usrp.set_tx_frequency(request, *channel).map(drop)
+            }
+            ScheduledAction::RxGain { gain, channel } => usrp.set_rx_gain(*gain, *channel, ""),
+            ScheduledAction::TxGain { gain, channel } => usrp.set_tx_gain(*gain, *channel, ""),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Scheduler;
+    use crate::{Error, TimeSpec};
+
+    #[test]
+    fn sample_times() {
+        let start = TimeSpec {
+            seconds: 10,
+            fraction: 0.5,
+        };
+        let scheduler = Scheduler::new(start.clone(), 1e6);
+        assert_eq!(scheduler.time_of(0).unwrap(), start);
+        assert_eq!(
+            scheduler.time_of(1_500_000).unwrap(),
+            TimeSpec {
+                seconds: 12,
+                fraction: 0.0,
+            }
+        );
+        let time = scheduler.time_of(250).unwrap();
+        assert_eq!(time.seconds, 10);
+        assert!((time.fraction - 0.50025).abs() < 1e-12);
+        assert!(matches!(
+            scheduler.time_of(u64::MAX),
+            Err(Error::SampleIndexTooLarge(u64::MAX))
+        ));
+    }
+}
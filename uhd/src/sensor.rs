@@ -0,0 +1,89 @@
+use std::ptr;
+
+use crate::error::{check_status, Error};
+use crate::utils::copy_string;
+
+/// The value of a sensor on a USRP
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SensorValue {
+    Bool(bool),
+    Int(i64),
+    Real(f64),
+    String(String),
+}
+
+/// A handle to a sensor value that UHD fills in (for internal use only)
+pub(crate) struct SensorValueHandle(uhd_sys::uhd_sensor_value_handle);
+
+impl SensorValueHandle {
+    /// Creates an empty sensor value
+    pub fn new() -> Result<Self, Error> {
+        let mut handle: uhd_sys::uhd_sensor_value_handle = ptr::null_mut();
+        check_status(unsafe { uhd_sys::uhd_sensor_value_make(&mut handle) })?;
+        Ok(SensorValueHandle(handle))
+    }
+
+    /// Converts the value into a SensorValue of the appropriate type
+    pub fn value(&self) -> Result<SensorValue, Error> {
+        use uhd_sys::uhd_sensor_value_data_type_t::*;
+        let mut data_type = UHD_SENSOR_VALUE_STRING;
+        check_status(unsafe { uhd_sys::uhd_sensor_value_data_type(self.0, &mut data_type) })?;
+        match data_type {
+            UHD_SENSOR_VALUE_BOOLEAN => {
+                let mut value = false;
+                check_status(unsafe { uhd_sys::uhd_sensor_value_to_bool(self.0, &mut value) })?;
+                Ok(SensorValue::Bool(value))
+            }
+            UHD_SENSOR_VALUE_INTEGER => {
+                // uhd_sensor_value_to_int() returns a 32-bit int, which is too small for
+                // values like GPS time. Parse the string form instead.
+                Ok(SensorValue::Int(parse_int(&self.string_value()?)?))
+            }
+            UHD_SENSOR_VALUE_REALNUM => {
+                let mut value = 0.0;
+                check_status(unsafe { uhd_sys::uhd_sensor_value_to_realnum(self.0, &mut value) })?;
+                Ok(SensorValue::Real(value))
+            }
+            _ => Ok(SensorValue::String(self.string_value()?)),
+        }
+    }
+
+    /// Returns the value formatted as a string
+    fn string_value(&self) -> Result<String, Error> {
+        copy_string(|buffer, length| unsafe {
+            uhd_sys::uhd_sensor_value_value(self.0, buffer, length as _)
+        })
+    }
+
+    /// Returns the underlying handle
+    pub fn handle_mut(&mut self) -> &mut uhd_sys::uhd_sensor_value_handle {
+        &mut self.0
+    }
+}
+
+impl Drop for SensorValueHandle {
+    fn drop(&mut self) {
+        let _ = unsafe { uhd_sys::uhd_sensor_value_free(&mut self.0) };
+    }
+}
+
+/// Parses the string form of an integer sensor value
+///
+/// This function returns Error::Type if the string is not a valid 64-bit integer.
+fn parse_int(value: &str) -> Result<i64, Error> {
+    value.trim().parse().map_err(|_| Error::Type)
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_int;
+
+    #[test]
+    fn parse_large_int() {
+        // A time after 2038, which does not fit in 32 bits
+        assert_eq!(parse_int("4102444800").unwrap(), 4_102_444_800);
+        assert_eq!(parse_int(" -12 ").unwrap(), -12);
+        assert!(parse_int("12.5").is_err());
+    }
+}
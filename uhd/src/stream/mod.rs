@@ -14,6 +14,26 @@ use std::marker::PhantomData;
 /// let args = StreamArgs::<Complex32>::new("sc16");
 /// ```
 ///
+/// The wire format does not need to match the host format. UHD converts between them. For
+/// example, the `sc8` wire format uses half of the bandwidth of `sc16`, which can prevent
+/// overflows on a busy USB connection:
+/// ```
+/// use uhd::StreamArgs;
+/// use num_complex::Complex32;
+/// let args = StreamArgs::<Complex32>::new("sc8");
+/// ```
+///
+/// Some devices also support the `sc12` wire format, which uses 3/4 of the bandwidth of `sc16`.
+/// For high-dynamic-range processing, the host format can be `fc64` (`Complex64`) while the
+/// wire format stays `sc16`:
+/// ```
+/// use uhd::StreamArgs;
+/// use num_complex::Complex64;
+/// let args = StreamArgs::<Complex64>::new("sc16");
+/// ```
+///
+/// Usrp::get_rx_stream() and Usrp::get_tx_stream() return Error::UnsupportedFormat if the
+/// device does not support a combination of formats.
 #[derive(Debug, Clone)]
 pub struct StreamArgs<I> {
     host_format: PhantomData<I>,
@@ -37,54 +57,92 @@ impl<I> StreamArgs<I> {
 
     /// Creates a builder, initialized with default arguments, that can be used to configure
     /// the stream arguments
+    ///
+    /// Only the options that differ from the defaults need to be set:
+    /// ```
+    /// use uhd::StreamArgs;
+    /// use num_complex::Complex32;
+    /// let args = StreamArgs::<Complex32>::builder()
+    ///     .wire_format("sc8")
+    ///     .channels([0, 1])
+    ///     .build();
+    /// ```
     pub fn builder() -> StreamArgsBuilder<I> {
         StreamArgsBuilder {
             args: StreamArgs::default(),
         }
     }
+
+    /// Returns one copy of these arguments for each channel, with only that channel
+    pub(crate) fn per_channel(&self) -> Vec<Self> {
+        self.channels
+            .iter()
+            .map(|&channel| StreamArgs {
+                host_format: PhantomData,
+                wire_format: self.wire_format.clone(),
+                args: self.args.clone(),
+                channels: vec![channel],
+            })
+            .collect()
+    }
 }
 
 impl<I> Default for StreamArgs<I> {
     /// Creates stream arguments with wire format `sc16`, host format determined by the type `I`,
-    /// and default arguments and channels
+    /// no additional arguments, and channel 0
     fn default() -> Self {
         StreamArgs {
             host_format: PhantomData::default(),
             wire_format: "sc16".to_string(),
             args: "".to_string(),
-            // Empty list = just channel 0
-            channels: vec![],
+            channels: vec![0],
         }
     }
 }
 
+/// A builder for StreamArgs
+///
+/// A partly configured builder can be cloned to create several similar sets of arguments.
+#[derive(Debug, Clone)]
 pub struct StreamArgsBuilder<I> {
     args: StreamArgs<I>,
 }
 
 impl<I> StreamArgsBuilder<I> {
     /// Sets the wire data format
-    pub fn wire_format(self, wire_format: String) -> Self {
+    pub fn wire_format<S>(self, wire_format: S) -> Self
+    where
+        S: Into<String>,
+    {
         StreamArgsBuilder {
             args: StreamArgs {
-                wire_format,
+                wire_format: wire_format.into(),
                 ..self.args
             },
         }
     }
 
     /// Sets additional arguments for the stream
-    pub fn args(self, args: String) -> Self {
+    pub fn args<S>(self, args: S) -> Self
+    where
+        S: Into<String>,
+    {
         StreamArgsBuilder {
-            args: StreamArgs { args, ..self.args },
+            args: StreamArgs {
+                args: args.into(),
+                ..self.args
+            },
         }
     }
 
     /// Sets the indexes of channels to stream
-    pub fn channels(self, channels: Vec<usize>) -> Self {
+    pub fn channels<C>(self, channels: C) -> Self
+    where
+        C: Into<Vec<usize>>,
+    {
         StreamArgsBuilder {
             args: StreamArgs {
-                channels,
+                channels: channels.into(),
                 ..self.args
             },
         }
@@ -99,6 +157,8 @@ impl<I> StreamArgsBuilder<I> {
 /// C-compatible version of StreamArgs
 pub(crate) struct StreamArgsC<'args> {
     pub host_format: CString,
+    /// The host format name, for error messages
+    pub host_format_name: &'static str,
     pub wire_format: CString,
     pub args: CString,
     pub channels: &'args [usize],
@@ -113,6 +173,7 @@ where
     fn try_from(args: &'args StreamArgs<I>) -> Result<Self, Self::Error> {
         Ok(StreamArgsC {
             host_format: CString::new(I::FORMAT)?,
+            host_format_name: I::FORMAT,
             wire_format: CString::new(&*args.wire_format)?,
             args: CString::new(&*args.args)?,
             channels: &args.channels,
@@ -139,6 +200,28 @@ impl Item for Complex<i8> {
     const FORMAT: &'static str = "sc8";
 }
 
+/// Operations that receive and transmit streamers have in common
+///
+/// Generic code that issues stream commands or needs to know the channels of a stream can use
+/// this trait without depending on the stream direction. The functions that transfer samples
+/// are different for each direction, so they are not part of this trait.
+pub trait Streamer {
+    /// The type of sample that this streamer transfers
+    type Item;
+
+    /// Returns the indexes of the channels that this streamer is associated with
+    fn channels(&self) -> &[usize];
+
+    /// Returns the number of channels that this streamer is associated with
+    fn num_channels(&self) -> usize;
+
+    /// Sends a stream command to the USRP
+    ///
+    /// UHD only supports stream commands on receive streams. Transmit streamers return
+    /// Error::NotImplemented.
+    fn send_command(&mut self, command: &StreamCommand) -> Result<(), crate::Error>;
+}
+
 /// A stream command that can be sent to a USRP to control streaming
 #[derive(Debug, Clone)]
 pub struct StreamCommand {
@@ -165,6 +248,10 @@ pub enum StreamCommandType {
 #[derive(Debug, Clone)]
 pub enum StreamTime {
     Now,
+    /// Start streaming when the USRP's clock reaches this time
+    ///
+    /// The time_spec() of the first ReceiveMetadata after the stream starts will be equal to
+    /// this time.
     Later(std::time::Duration),
 }
 
@@ -188,7 +275,7 @@ impl StreamCommand {
             StreamTime::Now => c_cmd.stream_now = true,
             StreamTime::Later(dur) => {
                 c_cmd.time_spec_full_secs = dur.as_secs() as i64;
-                c_cmd.time_spec_frac_secs = dur.subsec_millis() as f64 / 1000.0
+                c_cmd.time_spec_frac_secs = dur.subsec_nanos() as f64 / 1e9
             }
         }
 
@@ -218,3 +305,44 @@ impl StreamCommand {
         c_cmd
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{StreamArgs, StreamArgsC, StreamCommand, StreamCommandType, StreamTime};
+    use num_complex::Complex32;
+    use std::convert::TryFrom;
+    use std::time::Duration;
+
+    #[test]
+    fn wire_format_independent_of_host_format() {
+        let args = StreamArgs::<Complex32>::new("sc8");
+        let args_c = StreamArgsC::try_from(&args).unwrap();
+        assert_eq!("fc32", args_c.host_format.to_str().unwrap());
+        assert_eq!("sc8", args_c.wire_format.to_str().unwrap());
+    }
+
+    #[test]
+    fn per_channel_args() {
+        let args = StreamArgs::<Complex32>::builder()
+            .wire_format("sc8")
+            .channels([2, 0])
+            .build();
+        let split = args.per_channel();
+        assert_eq!(split.len(), 2);
+        assert_eq!(split[0].channels, [2]);
+        assert_eq!(split[1].channels, [0]);
+        assert!(split.iter().all(|args| args.wire_format == "sc8"));
+    }
+
+    #[test]
+    fn later_command_keeps_sub_millisecond_time() {
+        let command = StreamCommand {
+            time: StreamTime::Later(Duration::new(5, 250_000_250)),
+            command_type: StreamCommandType::StartContinuous,
+        };
+        let c_cmd = command.as_c_command();
+        assert!(!c_cmd.stream_now);
+        assert_eq!(5, c_cmd.time_spec_full_secs);
+        assert!((c_cmd.time_spec_frac_secs - 0.250_000_25).abs() < 1e-15);
+    }
+}
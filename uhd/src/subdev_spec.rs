@@ -0,0 +1,155 @@
+use std::ffi::CString;
+use std::fmt;
+use std::ptr;
+use std::str::FromStr;
+
+use crate::error::{check_status, Error};
+use crate::utils::copy_string;
+
+/// A subdevice specification, which maps channel indexes to daughterboard front ends
+///
+/// The markup string form is a list of `daughterboard:subdevice` pairs separated by spaces.
+/// For example, `A:0 B:0` maps channel 0 to front end 0 of daughterboard A, and channel 1 to
+/// front end 0 of daughterboard B.
+///
+/// ```
+/// use uhd::SubdevSpec;
+/// let spec: SubdevSpec = "A:0 B:0".parse().unwrap();
+/// assert_eq!(spec.pairs()[1].daughterboard(), "B");
+/// assert_eq!(spec.to_string(), "A:0 B:0");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SubdevSpec {
+    pairs: Vec<SubdevSpecPair>,
+}
+
+impl SubdevSpec {
+    /// Returns the front ends in channel order
+    pub fn pairs(&self) -> &[SubdevSpecPair] {
+        &self.pairs
+    }
+}
+
+/// A daughterboard and a subdevice (front end) on that daughterboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubdevSpecPair {
+    daughterboard: String,
+    subdevice: String,
+}
+
+impl SubdevSpecPair {
+    /// Creates a pair from a daughterboard slot name and a subdevice name
+    pub fn new<D, S>(daughterboard: D, subdevice: S) -> Self
+    where
+        D: Into<String>,
+        S: Into<String>,
+    {
+        SubdevSpecPair {
+            daughterboard: daughterboard.into(),
+            subdevice: subdevice.into(),
+        }
+    }
+
+    /// Returns the name of the daughterboard slot (for example, `A`)
+    pub fn daughterboard(&self) -> &str {
+        &self.daughterboard
+    }
+
+    /// Returns the name of the subdevice on the daughterboard (for example, `0`)
+    pub fn subdevice(&self) -> &str {
+        &self.subdevice
+    }
+}
+
+impl From<Vec<SubdevSpecPair>> for SubdevSpec {
+    fn from(pairs: Vec<SubdevSpecPair>) -> Self {
+        SubdevSpec { pairs }
+    }
+}
+
+impl FromStr for SubdevSpec {
+    type Err = Error;
+
+    /// Parses a subdevice specification from its markup string
+    ///
+    /// A pair without a colon has an empty subdevice name.
+    fn from_str(markup: &str) -> Result<Self, Self::Err> {
+        let pairs = markup
+            .split_whitespace()
+            .map(|pair| {
+                let mut parts = pair.splitn(2, ':');
+                let daughterboard = parts.next().unwrap_or_default();
+                let subdevice = parts.next().unwrap_or_default();
+                SubdevSpecPair::new(daughterboard, subdevice)
+            })
+            .collect();
+        Ok(SubdevSpec { pairs })
+    }
+}
+
+impl fmt::Display for SubdevSpec {
+    /// Formats this specification as a markup string
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, pair) in self.pairs.iter().enumerate() {
+            if i != 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}:{}", pair.daughterboard, pair.subdevice)?;
+        }
+        Ok(())
+    }
+}
+
+/// A handle to a UHD subdevice specification (for internal use only)
+pub(crate) struct SubdevSpecHandle(uhd_sys::uhd_subdev_spec_handle);
+
+impl SubdevSpecHandle {
+    /// Creates a UHD subdevice specification from a SubdevSpec
+    pub fn new(spec: &SubdevSpec) -> Result<Self, Error> {
+        let markup = CString::new(spec.to_string())?;
+        let mut handle: uhd_sys::uhd_subdev_spec_handle = ptr::null_mut();
+        check_status(unsafe { uhd_sys::uhd_subdev_spec_make(&mut handle, markup.as_ptr()) })?;
+        Ok(SubdevSpecHandle(handle))
+    }
+
+    /// Converts this UHD subdevice specification into a SubdevSpec
+    pub fn spec(&self) -> Result<SubdevSpec, Error> {
+        copy_string(|buffer, length| unsafe {
+            uhd_sys::uhd_subdev_spec_to_string(self.0, buffer, length as _)
+        })?
+        .parse()
+    }
+
+    /// Returns the underlying handle
+    pub fn handle(&self) -> uhd_sys::uhd_subdev_spec_handle {
+        self.0
+    }
+}
+
+impl Drop for SubdevSpecHandle {
+    fn drop(&mut self) {
+        let _ = unsafe { uhd_sys::uhd_subdev_spec_free(&mut self.0) };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SubdevSpec, SubdevSpecPair};
+
+    #[test]
+    fn parse_and_format() {
+        let spec: SubdevSpec = " A:0  B:AB ".parse().unwrap();
+        assert_eq!(
+            spec.pairs(),
+            &[
+                SubdevSpecPair::new("A", "0"),
+                SubdevSpecPair::new("B", "AB")
+            ]
+        );
+        assert_eq!(spec.to_string(), "A:0 B:AB");
+
+        let spec: SubdevSpec = "A".parse().unwrap();
+        assert_eq!(spec.pairs(), &[SubdevSpecPair::new("A", "")]);
+        assert!("".parse::<SubdevSpec>().unwrap().pairs().is_empty());
+    }
+}
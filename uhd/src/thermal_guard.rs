@@ -0,0 +1,87 @@
+use std::time::{Duration, Instant};
+
+use crate::{Error, Usrp};
+
+/// Checks the temperature of a transmit channel at intervals during a long transmission
+///
+/// A transmit loop calls check() once per iteration. When the interval has passed since the
+/// last reading, check() reads the temperature with Usrp::get_tx_temperature() and returns
+/// Error::Overheated if it has reached the limit, so the loop can reduce the gain or stop
+/// transmitting before the power amplifier is damaged. Other calls return immediately, so
+/// checking does not slow down the loop.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use num_complex::Complex32;
+/// # use uhd::{StreamArgs, ThermalGuard, Usrp};
+/// # fn main() -> Result<(), uhd::Error> {
+/// let usrp = Usrp::open("")?;
+/// let mut streamer = usrp.get_tx_stream(&StreamArgs::<Complex32>::new("sc16"))?;
+/// let mut guard = ThermalGuard::new(0, 70.0, Duration::from_secs(1));
+/// let samples = vec![Complex32::new(0.5, 0.0); 4096];
+/// loop {
+///     guard.check(&usrp)?;
+///     streamer.transmit_simple_with(&samples, 0.1)?;
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThermalGuard {
+    /// The transmit channel to check
+    channel: usize,
+    /// The temperature, in degrees Celsius, at which check() returns an error
+    limit: f64,
+    /// The minimum time between temperature readings
+    interval: Duration,
+    /// The time of the last reading, or None if the temperature has not been read
+    last_check: Option<Instant>,
+    /// The last temperature read, in degrees Celsius
+    last_temperature: Option<f64>,
+}
+
+impl ThermalGuard {
+    /// Creates a guard for a transmit channel with a temperature limit in degrees Celsius
+    /// and a minimum time between readings
+    ///
+    /// The first call to check() always reads the temperature.
+    pub fn new(channel: usize, limit: f64, interval: Duration) -> Self {
+        ThermalGuard {
+            channel,
+            limit,
+            interval,
+            last_check: None,
+            last_temperature: None,
+        }
+    }
+
+    /// Reads the temperature if the interval has passed since the last reading, and returns
+    /// Error::Overheated if the last temperature read is at or above the limit
+    ///
+    /// Errors from reading the temperature sensor are also returned.
+    pub fn check(&mut self, usrp: &Usrp) -> Result<(), Error> {
+        let due = self
+            .last_check
+            .map(|last_check| last_check.elapsed() >= self.interval)
+            .unwrap_or(true);
+        if due {
+            self.last_temperature = Some(usrp.get_tx_temperature(self.channel)?);
+            self.last_check = Some(Instant::now());
+        }
+        match self.last_temperature {
+            Some(temperature) if temperature >= self.limit => Err(Error::Overheated {
+                channel: self.channel,
+                temperature,
+                limit: self.limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the last temperature read, in degrees Celsius, or None if check() has not read
+    /// the temperature yet
+    pub fn last_temperature(&self) -> Option<f64> {
+        self.last_temperature
+    }
+}
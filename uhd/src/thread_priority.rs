@@ -0,0 +1,33 @@
+use std::cell::Cell;
+
+use crate::error::{check_status, Error};
+
+thread_local! {
+    /// The priority most recently set on this thread with set_thread_priority()
+    static PRIORITY: Cell<Option<f32>> = Cell::new(None);
+}
+
+/// Sets the scheduling priority of the current thread
+///
+/// priority: A value in the range [-1, 1]. 0 is the normal priority and 1 is the highest
+/// priority.
+///
+/// realtime: If this is true, the thread uses real-time scheduling. This usually requires
+/// extra permissions (for example, `rtprio` in `/etc/security/limits.conf` on Linux).
+///
+/// This function returns an error if the operating system does not allow the change. UHD
+/// recommends a high priority for threads that receive or transmit samples.
+pub fn set_thread_priority(priority: f32, realtime: bool) -> Result<(), Error> {
+    check_status(unsafe { uhd_sys::uhd_set_thread_priority(priority, realtime) })?;
+    PRIORITY.with(|current| current.set(Some(priority)));
+    Ok(())
+}
+
+/// Returns the priority that set_thread_priority() most recently set on the current thread, or
+/// None if it has not successfully set a priority on this thread
+///
+/// UHD does not provide a way to read the priority back from the operating system, so this
+/// only reflects calls to set_thread_priority() that succeeded.
+pub fn thread_priority() -> Option<f32> {
+    PRIORITY.with(Cell::get)
+}
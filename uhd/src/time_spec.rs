@@ -0,0 +1,279 @@
+use std::convert::TryFrom;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::time::Duration;
+
+use crate::error::Error;
+
+/// A time value, represented as an integer number of seconds and a floating-point fraction of
+/// a second
+///
+/// The results of addition and subtraction are normalized: whole seconds are carried out of the
+/// fraction, so the fraction stays in the range [0, 1). This keeps the precision of the fraction
+/// when a time is advanced by a small step many times.
+#[derive(Debug, Clone, Default, PartialOrd, PartialEq)]
+pub struct TimeSpec {
+    // In some versions of UHD, the corresponding field of uhd::time_spec_t is a time_t.
+    // In other versions, it's a int64_t. The Rust code does conversion to keep this
+    // an i64.
+    pub seconds: i64,
+    pub fraction: f64,
+}
+
+impl TimeSpec {
+    /// Creates a time from whole seconds and a fraction of a second
+    ///
+    /// This function returns Error::InvalidTimeSpec if the fraction is not in the range [0, 1)
+    /// (including if it is NaN). Functions that send a time to UHD perform the same check, but
+    /// this allows an invalid time to be detected where it is created.
+    pub fn new(seconds: i64, fraction: f64) -> Result<Self, Error> {
+        let time = TimeSpec { seconds, fraction };
+        time.check_fraction()?;
+        Ok(time)
+    }
+
+    /// Returns Error::InvalidTimeSpec if the fraction is not in the range [0, 1)
+    pub(crate) fn check_fraction(&self) -> Result<(), Error> {
+        if (0.0..1.0).contains(&self.fraction) {
+            Ok(())
+        } else {
+            Err(Error::InvalidTimeSpec)
+        }
+    }
+
+    /// Converts this time into a Duration, rounded to the nearest nanosecond
+    ///
+    /// This function returns Error::InvalidTimeSpec if this time is negative or its fraction
+    /// is not in the range [0, 1).
+    pub fn to_duration(&self) -> Result<Duration, Error> {
+        self.check_fraction()?;
+        let seconds = u64::try_from(self.seconds).map_err(|_| Error::InvalidTimeSpec)?;
+        // Duration::new() carries a rounded value of 1e9 nanoseconds into the seconds
+        let nanoseconds = (self.fraction * 1e9).round() as u32;
+        Ok(Duration::new(seconds, nanoseconds))
+    }
+
+    /// Converts this time into a number of ticks of a clock with the provided rate, in hertz,
+    /// rounded to the nearest tick
+    ///
+    /// The whole seconds are multiplied by the integer part of the rate using integer
+    /// arithmetic, so large times keep single-tick precision. This matches
+    /// uhd::time_spec_t::to_ticks().
+    ///
+    /// This function returns Error::InvalidTickRate if the rate is not finite or is less than
+    /// 1 Hz.
+    pub fn to_ticks(&self, tick_rate: f64) -> Result<i64, Error> {
+        check_tick_rate(tick_rate)?;
+        let rate_whole = tick_rate.trunc();
+        let rate_fraction = tick_rate - rate_whole;
+        let ticks_whole = self.seconds * rate_whole as i64;
+        let ticks_error = rate_fraction * self.seconds as f64;
+        let ticks_fraction = self.fraction * tick_rate;
+        Ok(ticks_whole + (ticks_error + ticks_fraction).round() as i64)
+    }
+
+    /// Creates a time from a number of ticks of a clock with the provided rate, in hertz
+    ///
+    /// This is the inverse of to_ticks(), and matches uhd::time_spec_t::from_ticks(). It
+    /// returns Error::InvalidTickRate if the rate is not finite or is less than 1 Hz.
+    pub fn from_ticks(ticks: i64, tick_rate: f64) -> Result<Self, Error> {
+        check_tick_rate(tick_rate)?;
+        let rate_whole = tick_rate.trunc() as i64;
+        let rate_fraction = tick_rate - rate_whole as f64;
+        let seconds = ticks.div_euclid(rate_whole);
+        let ticks_error = ticks.rem_euclid(rate_whole);
+        let ticks_fraction = ticks_error as f64 - seconds as f64 * rate_fraction;
+        Ok(TimeSpec::normalized(seconds, ticks_fraction / tick_rate))
+    }
+
+    /// Creates a time from seconds and a fraction that may be outside [0, 1), moving whole
+    /// seconds from the fraction into the seconds
+    fn normalized(seconds: i64, fraction: f64) -> Self {
+        let whole = fraction.floor();
+        let mut time = TimeSpec {
+            seconds: seconds + whole as i64,
+            fraction: fraction - whole,
+        };
+        // Rounding can make the fraction exactly 1.0
+        if time.fraction >= 1.0 {
+            time.seconds += 1;
+            time.fraction -= 1.0;
+        }
+        time
+    }
+}
+
+/// Returns Error::InvalidTickRate if a tick rate is not finite or is less than 1 Hz
+fn check_tick_rate(tick_rate: f64) -> Result<(), Error> {
+    if tick_rate.is_finite() && tick_rate >= 1.0 {
+        Ok(())
+    } else {
+        Err(Error::InvalidTickRate(tick_rate))
+    }
+}
+
+impl From<Duration> for TimeSpec {
+    /// Converts a duration into a time with the same number of seconds and nanoseconds
+    ///
+    /// Durations longer than i64::MAX seconds saturate.
+    fn from(duration: Duration) -> Self {
+        TimeSpec {
+            seconds: i64::try_from(duration.as_secs()).unwrap_or(i64::MAX),
+            fraction: f64::from(duration.subsec_nanos()) / 1e9,
+        }
+    }
+}
+
+impl Add for TimeSpec {
+    type Output = TimeSpec;
+
+    fn add(self, other: TimeSpec) -> TimeSpec {
+        TimeSpec::normalized(self.seconds + other.seconds, self.fraction + other.fraction)
+    }
+}
+
+impl AddAssign for TimeSpec {
+    fn add_assign(&mut self, other: TimeSpec) {
+        *self = self.clone() + other;
+    }
+}
+
+impl Sub for TimeSpec {
+    type Output = TimeSpec;
+
+    fn sub(self, other: TimeSpec) -> TimeSpec {
+        TimeSpec::normalized(self.seconds - other.seconds, self.fraction - other.fraction)
+    }
+}
+
+impl SubAssign for TimeSpec {
+    fn sub_assign(&mut self, other: TimeSpec) {
+        *self = self.clone() - other;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TimeSpec;
+    use crate::error::Error;
+    use std::time::Duration;
+
+    #[test]
+    fn carry_and_borrow() {
+        let sum = TimeSpec {
+            seconds: 1,
+            fraction: 0.75,
+        } + TimeSpec {
+            seconds: 2,
+            fraction: 0.5,
+        };
+        assert_eq!(sum.seconds, 4);
+        assert!((sum.fraction - 0.25).abs() < 1e-15);
+
+        let difference = TimeSpec {
+            seconds: 3,
+            fraction: 0.25,
+        } - TimeSpec {
+            seconds: 1,
+            fraction: 0.5,
+        };
+        assert_eq!(difference.seconds, 1);
+        assert!((difference.fraction - 0.75).abs() < 1e-15);
+    }
+
+    #[test]
+    fn checked_new() {
+        let time = TimeSpec::new(-2, 0.5).unwrap();
+        assert_eq!(time.seconds, -2);
+        assert_eq!(time.fraction, 0.5);
+        assert!(TimeSpec::new(0, 0.0).is_ok());
+        assert!(TimeSpec::new(0, 1.0).is_err());
+        assert!(TimeSpec::new(0, -0.1).is_err());
+        assert!(TimeSpec::new(0, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn many_small_steps() {
+        let step = TimeSpec {
+            seconds: 0,
+            fraction: 1e-9,
+        };
+        let mut time = TimeSpec::default();
+        for _ in 0..1_000_000 {
+            time += step.clone();
+        }
+        assert_eq!(time.seconds, 0);
+        assert!((time.fraction - 0.001).abs() < 1e-12);
+    }
+
+    #[test]
+    fn duration_round_trip() {
+        let duration = Duration::new(3, 250_000_001);
+        let time = TimeSpec::from(duration);
+        assert_eq!(time.seconds, 3);
+        assert!((time.fraction - 0.250_000_001).abs() < 1e-15);
+        assert_eq!(time.to_duration().unwrap(), duration);
+
+        let almost_one = TimeSpec {
+            seconds: 1,
+            fraction: 0.999_999_999_9,
+        };
+        assert_eq!(almost_one.to_duration().unwrap(), Duration::from_secs(2));
+
+        let negative = TimeSpec {
+            seconds: -1,
+            fraction: 0.5,
+        };
+        assert!(negative.to_duration().is_err());
+    }
+
+    #[test]
+    fn ticks_round_trip() {
+        for &rate in &[200e6, 184.32e6, 1e6 / 3.0] {
+            for &ticks in &[0, 1, 12_345, 3_600_000_000_017, -7] {
+                let time = TimeSpec::from_ticks(ticks, rate).unwrap();
+                assert!((0.0..1.0).contains(&time.fraction));
+                assert_eq!(time.to_ticks(rate).unwrap(), ticks);
+            }
+        }
+        let time = TimeSpec::from_ticks(200_000_001, 200e6).unwrap();
+        assert_eq!(time.seconds, 1);
+        assert!((time.fraction - 5e-9).abs() < 1e-18);
+    }
+
+    #[test]
+    fn fractional_tick_rates() {
+        for &rate in &[1.0, 1.5, 2.5, 7.25] {
+            for &ticks in &[0, 1, 2, 3, 1_000_003, -5] {
+                let time = TimeSpec::from_ticks(ticks, rate).unwrap();
+                assert!((0.0..1.0).contains(&time.fraction));
+                assert_eq!(time.to_ticks(rate).unwrap(), ticks);
+            }
+        }
+        // 5 ticks at 2.5 Hz are 2 seconds
+        assert_eq!(
+            TimeSpec::from_ticks(5, 2.5).unwrap(),
+            TimeSpec {
+                seconds: 2,
+                fraction: 0.0,
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_tick_rates() {
+        let time = TimeSpec {
+            seconds: 1,
+            fraction: 0.5,
+        };
+        for &rate in &[0.5, 0.0, -1e6, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                TimeSpec::from_ticks(1, rate),
+                Err(Error::InvalidTickRate(_))
+            ));
+            assert!(matches!(
+                time.to_ticks(rate),
+                Err(Error::InvalidTickRate(_))
+            ));
+        }
+    }
+}
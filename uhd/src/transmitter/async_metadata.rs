@@ -0,0 +1,154 @@
+use std::ptr;
+
+use crate::error::{check_status, Error};
+use crate::TimeSpec;
+
+/// Information about a transmit operation that the USRP reports asynchronously, after the
+/// samples have been sent
+pub struct AsyncMetadata {
+    /// Handle to C++ object, or null if UHD has not allocated it
+    handle: uhd_sys::uhd_async_metadata_handle,
+}
+
+impl AsyncMetadata {
+    /// Creates empty metadata that a receive_async_message() call can fill in
+    ///
+    /// This function returns an error if UHD fails to allocate the metadata.
+    pub fn new() -> Result<Self, Error> {
+        let mut handle: uhd_sys::uhd_async_metadata_handle = ptr::null_mut();
+        check_status(unsafe { uhd_sys::uhd_async_metadata_make(&mut handle) })?;
+        Ok(AsyncMetadata { handle })
+    }
+
+    /// Returns the index of the channel that this event applies to
+    ///
+    /// This function returns an error if UHD fails to read the metadata.
+    pub fn channel(&self) -> Result<usize, Error> {
+        let mut channel = 0usize;
+        if self.handle.is_null() {
+            return Ok(channel);
+        }
+        check_status(unsafe {
+            uhd_sys::uhd_async_metadata_channel(self.handle, &mut channel as *mut usize as *mut _)
+        })?;
+        Ok(channel)
+    }
+
+    /// Returns the time when the event occurred, according to the USRP's internal clock
+    ///
+    /// This function returns an error if UHD fails to read the metadata.
+    pub fn time_spec(&self) -> Result<Option<TimeSpec>, Error> {
+        if self.has_time_spec()? {
+            let mut time = TimeSpec::default();
+            let mut seconds_time_t: libc::time_t = Default::default();
+
+            check_status(unsafe {
+                uhd_sys::uhd_async_metadata_time_spec(
+                    self.handle,
+                    &mut seconds_time_t,
+                    &mut time.fraction,
+                )
+            })?;
+            // Convert seconds from time_t to i64
+            time.seconds = seconds_time_t.into();
+            Ok(Some(time))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns true if this metadata object has a time
+    fn has_time_spec(&self) -> Result<bool, Error> {
+        let mut has = false;
+        if self.handle.is_null() {
+            return Ok(has);
+        }
+        check_status(unsafe { uhd_sys::uhd_async_metadata_has_time_spec(self.handle, &mut has) })?;
+        Ok(has)
+    }
+
+    /// Returns the event that this metadata reports
+    ///
+    /// This function returns an error if UHD fails to read the metadata. Metadata that UHD has
+    /// not allocated reports AsyncEvent::Other.
+    pub fn event(&self) -> Result<AsyncEvent, Error> {
+        use uhd_sys::uhd_async_metadata_event_code_t::*;
+        if self.handle.is_null() {
+            return Ok(AsyncEvent::Other);
+        }
+        let mut code = UHD_ASYNC_METADATA_EVENT_CODE_BURST_ACK;
+        check_status(unsafe { uhd_sys::uhd_async_metadata_event_code(self.handle, &mut code) })?;
+        Ok(match code {
+            UHD_ASYNC_METADATA_EVENT_CODE_BURST_ACK => AsyncEvent::BurstAck,
+            UHD_ASYNC_METADATA_EVENT_CODE_UNDERFLOW => AsyncEvent::Underflow,
+            UHD_ASYNC_METADATA_EVENT_CODE_SEQ_ERROR => AsyncEvent::SequenceError,
+            UHD_ASYNC_METADATA_EVENT_CODE_TIME_ERROR => AsyncEvent::TimeError,
+            UHD_ASYNC_METADATA_EVENT_CODE_UNDERFLOW_IN_PACKET => AsyncEvent::UnderflowInPacket,
+            UHD_ASYNC_METADATA_EVENT_CODE_SEQ_ERROR_IN_BURST => AsyncEvent::SequenceErrorInBurst,
+            UHD_ASYNC_METADATA_EVENT_CODE_USER_PAYLOAD => AsyncEvent::UserPayload,
+            _ => AsyncEvent::Other,
+        })
+    }
+
+    pub(crate) fn handle_mut(&mut self) -> &mut uhd_sys::uhd_async_metadata_handle {
+        &mut self.handle
+    }
+}
+
+// Thread safety: The uhd_async_metadata struct just stores data. All exposed functions read fields.
+unsafe impl Send for AsyncMetadata {}
+unsafe impl Sync for AsyncMetadata {}
+
+impl Default for AsyncMetadata {
+    fn default() -> Self {
+        AsyncMetadata::new().unwrap()
+    }
+}
+
+impl Drop for AsyncMetadata {
+    fn drop(&mut self) {
+        if self.handle.is_null() {
+            return;
+        }
+        let _ = unsafe { uhd_sys::uhd_async_metadata_free(&mut self.handle) };
+    }
+}
+
+/// Events that a USRP can report about a transmit operation
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum AsyncEvent {
+    /// A burst was transmitted successfully
+    BurstAck,
+    /// An internal send buffer ran out of samples
+    Underflow,
+    /// A packet was lost between the host and the device
+    SequenceError,
+    /// A packet had a time that was in the past
+    ///
+    /// The packet was not transmitted. The burst can be sent again with a later time.
+    TimeError,
+    /// An internal send buffer ran out of samples in the middle of a packet
+    UnderflowInPacket,
+    /// A packet was lost in the middle of a burst
+    SequenceErrorInBurst,
+    /// The event contains a user payload
+    UserPayload,
+    /// Some other event
+    Other,
+}
+
+mod fmt {
+    use super::AsyncMetadata;
+    use std::fmt::{Debug, Formatter, Result};
+
+    impl Debug for AsyncMetadata {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            f.debug_struct("AsyncMetadata")
+                .field("channel", &self.channel().ok())
+                .field("time_spec", &self.time_spec().ok().flatten())
+                .field("event", &self.event().ok())
+                .finish()
+        }
+    }
+}
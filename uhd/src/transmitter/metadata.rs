@@ -1,20 +1,69 @@
 use std::ptr;
 
-use crate::error::check_status;
+use crate::error::{check_status, Error};
 
 use crate::TimeSpec;
 
 /// Data about a transmit operation
 pub struct TransmitMetadata {
-    /// Handle to C++ object
+    /// Handle to C++ object, or null for metadata that was created without UHD
     handle: uhd_sys::uhd_tx_metadata_handle,
     /// Number of samples transmitted
     samples: usize,
 }
 
 impl TransmitMetadata {
-    pub fn new() -> Self {
-        Default::default()
+    /// Creates metadata with no time and no burst flags
+    ///
+    /// This function returns an error if UHD fails to allocate the metadata.
+    pub fn new() -> Result<Self, Error> {
+        TransmitMetadata::with_options(None, false, false)
+    }
+
+    /// Creates metadata for a transmit operation
+    ///
+    /// time_spec: The time when the first sample should be transmitted, or None to transmit
+    /// as soon as possible
+    ///
+    /// start_of_burst: If this is true, the samples are the first samples of a burst
+    ///
+    /// end_of_burst: If this is true, the samples are the last samples of a burst
+    ///
+    /// This function returns an error if UHD fails to allocate the metadata.
+    pub fn with_options(
+        time_spec: Option<&TimeSpec>,
+        start_of_burst: bool,
+        end_of_burst: bool,
+    ) -> Result<Self, Error> {
+        let mut handle: uhd_sys::uhd_tx_metadata_handle = ptr::null_mut();
+        let (full_secs, frac_secs) = time_spec
+            .map(|time| (time.seconds, time.fraction))
+            .unwrap_or_default();
+
+        check_status(unsafe {
+            uhd_sys::uhd_tx_metadata_make(
+                &mut handle,
+                time_spec.is_some(),
+                // Convert seconds from i64 to time_t or int64_t, depending on the UHD version
+                full_secs as _,
+                frac_secs,
+                start_of_burst,
+                end_of_burst,
+            )
+        })?;
+        Ok(TransmitMetadata { handle, samples: 0 })
+    }
+
+    /// Creates metadata with the provided number of samples without using UHD (for the mock
+    /// streamers only)
+    ///
+    /// The metadata has no time and no burst flags.
+    #[cfg(feature = "mock")]
+    pub(crate) fn without_handle(samples: usize) -> Self {
+        TransmitMetadata {
+            handle: ptr::null_mut(),
+            samples,
+        }
     }
 
     /// Returns the timestamp of (the first?) of the transmitted samples, according to the USRP's
@@ -42,6 +91,9 @@ impl TransmitMetadata {
 
     /// Returns true if this metadata object has a time
     fn has_time_spec(&self) -> bool {
+        if self.handle.is_null() {
+            return false;
+        }
         let mut has = false;
         check_status(unsafe { uhd_sys::uhd_tx_metadata_has_time_spec(self.handle, &mut has) })
             .unwrap();
@@ -50,6 +102,9 @@ impl TransmitMetadata {
 
     /// Returns true if the transmitted samples are at the beginning of a burst
     pub fn start_of_burst(&self) -> bool {
+        if self.handle.is_null() {
+            return false;
+        }
         let mut value = false;
         check_status(unsafe { uhd_sys::uhd_tx_metadata_start_of_burst(self.handle, &mut value) })
             .unwrap();
@@ -58,6 +113,9 @@ impl TransmitMetadata {
 
     /// Returns true if the transmitted samples are at the end of a burst
     pub fn end_of_burst(&self) -> bool {
+        if self.handle.is_null() {
+            return false;
+        }
         let mut value = false;
         check_status(unsafe { uhd_sys::uhd_tx_metadata_end_of_burst(self.handle, &mut value) })
             .unwrap();
@@ -85,32 +143,15 @@ unsafe impl Sync for TransmitMetadata {}
 
 impl Default for TransmitMetadata {
     fn default() -> Self {
-        let mut handle: uhd_sys::uhd_tx_metadata_handle = ptr::null_mut();
-
-        // not sure what to do here, need to look at docs
-        let has_time_spec = Default::default();
-        let full_secs = Default::default();
-        let frac_secs = Default::default();
-        let start_of_burst = Default::default();
-        let end_of_burst = Default::default();
-
-        check_status(unsafe {
-            uhd_sys::uhd_tx_metadata_make(
-                &mut handle,
-                has_time_spec,
-                full_secs,
-                frac_secs,
-                start_of_burst,
-                end_of_burst,
-            )
-        })
-        .unwrap();
-        TransmitMetadata { handle, samples: 0 }
+        TransmitMetadata::new().unwrap()
     }
 }
 
 impl Drop for TransmitMetadata {
     fn drop(&mut self) {
+        if self.handle.is_null() {
+            return;
+        }
         let _ = unsafe { uhd_sys::uhd_tx_metadata_free(&mut self.handle) };
     }
 }
@@ -142,4 +183,12 @@ mod test {
         assert_eq!(false, metadata.start_of_burst());
         assert_eq!(false, metadata.end_of_burst());
     }
+
+    #[test]
+    fn burst_tx_metadata() {
+        let metadata = TransmitMetadata::with_options(None, true, true).unwrap();
+        assert_eq!(None, metadata.time_spec());
+        assert_eq!(true, metadata.start_of_burst());
+        assert_eq!(true, metadata.end_of_burst());
+    }
 }
@@ -1,3 +1,4 @@
+pub mod async_metadata;
 pub mod info;
 pub mod metadata;
 pub mod streamer;
@@ -1,12 +1,17 @@
+use std::io::{self, Read};
 use std::marker::PhantomData;
+use std::mem;
 use std::os::raw::c_void;
 use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::{
     error::{check_status, Error},
+    sample_file::FileSample,
+    stream::{StreamCommand, Streamer},
     usrp::Usrp,
-    utils::check_equal_buffer_lengths,
-    TransmitMetadata,
+    utils::{c_buffer_length, check_equal_buffer_lengths, packet_timeout},
+    AsyncEvent, AsyncMetadata, TransmitMetadata,
 };
 
 /// A streamer used to transmit samples from a USRP
@@ -20,23 +25,40 @@ pub struct TransmitStreamer<'usrp, I> {
     /// A vector of pointers to buffers (used in transmit() to convert `&mut [&[I]]` to `*mut *const I`
     /// without reallocating memory each time
     ///
-    /// Invariant: If this is not empty, its length is equal to the value returned by
-    /// self.num_channels().
+    /// Invariant: If this is not empty, its length is equal to the number of channels that
+    /// this streamer was created with.
     buffer_pointers: Vec<*const c_void>,
+    /// Indexes of the channels that this streamer was created with
+    channels: Vec<usize>,
+    /// The transmit sample rate when this streamer was created, in samples per second
+    rate: f64,
+    /// The value from max_num_samps(), or 0 if it has not been queried yet
+    max_num_samps: AtomicUsize,
     /// Link to the USRP that this streamer is associated with
     usrp: PhantomData<&'usrp Usrp>,
     /// Item type phantom data
     item_phantom: PhantomData<I>,
 }
 
-impl<I> TransmitStreamer<'_, I> {
+impl<'usrp, I> TransmitStreamer<'usrp, I> {
     /// Creates a transmit streamer with a null streamer handle (for internal use only)
     ///
     /// After creating a streamer with this function, its streamer handle must be initialized.
-    pub(crate) fn new() -> Self {
+    ///
+    /// channels: The channel indexes from the stream arguments. An empty list means channel 0.
+    ///
+    /// rate: The current transmit sample rate
+    pub(crate) fn new(channels: &[usize], rate: f64) -> Self {
         TransmitStreamer {
             handle: ptr::null_mut(),
             buffer_pointers: Vec::new(),
+            channels: if channels.is_empty() {
+                vec![0]
+            } else {
+                channels.to_vec()
+            },
+            rate,
+            max_num_samps: AtomicUsize::new(0),
             usrp: PhantomData,
             item_phantom: PhantomData,
         }
@@ -51,6 +73,60 @@ impl<I> TransmitStreamer<'_, I> {
         self.handle
     }
 
+    /// Returns the underlying UHD streamer handle, for calling UHD functions that this library
+    /// does not wrap
+    ///
+    /// # Safety
+    ///
+    /// The handle must not be freed, and must not be used after this streamer is dropped. Calls
+    /// through the handle must follow the same threading rules as the functions of this streamer.
+    #[cfg(feature = "raw-handle")]
+    pub unsafe fn raw_handle(&self) -> uhd_sys::uhd_tx_streamer_handle {
+        self.handle
+    }
+
+    /// Returns the indexes of the channels that this streamer is associated with
+    pub fn channels(&self) -> &[usize] {
+        &self.channels
+    }
+
+    /// Returns the transmit sample rate when this streamer was created, in samples per second
+    ///
+    /// If the sample rate is changed after this streamer is created, this function still
+    /// returns the old rate.
+    pub fn configured_rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Returns a timeout, in seconds, for transmitting one packet at configured_rate()
+    ///
+    /// This is twice the time that one packet of max_num_samps() samples takes at the sample
+    /// rate, but at least 10 milliseconds to allow for host scheduling delays. A fixed timeout
+    /// such as 0.1 seconds is longer than needed at high sample rates and can be too short at
+    /// very low sample rates.
+    pub fn default_timeout(&self) -> Result<f64, Error> {
+        Ok(packet_timeout(self.max_num_samps()?, self.rate))
+    }
+
+    /// Returns the maximum number of samples per channel in one packet
+    ///
+    /// The value is queried from UHD on the first successful call and cached.
+    pub fn max_num_samps(&self) -> Result<usize, Error> {
+        let cached = self.max_num_samps.load(Ordering::Relaxed);
+        if cached != 0 {
+            return Ok(cached);
+        }
+        let mut samples = 0usize;
+        check_status(unsafe {
+            uhd_sys::uhd_tx_streamer_max_num_samps(
+                self.handle,
+                &mut samples as *mut usize as *mut _,
+            )
+        })?;
+        self.max_num_samps.store(samples, Ordering::Relaxed);
+        Ok(samples)
+    }
+
     /// Returns the number of channels that this streamer is associated with
     pub fn num_channels(&self) -> usize {
         let mut num_channels = 0usize;
@@ -70,31 +146,65 @@ impl<I> TransmitStreamer<'_, I> {
     /// buffers should have the same length. This function will panic if the number of buffers
     /// is not equal to self.num_channels(), or if not all buffers have the same length.
     ///
-    /// timeout: The timeout for the transmit operation, in seconds
+    /// UHD always streams every channel that the streamer was created with, so a channel
+    /// cannot be disabled by passing an empty buffer for it. To keep a channel silent without
+    /// creating a new streamer, pass a buffer of zeros with the same length.
+    ///
+    /// timeout: The timeout for the transmit operation, in seconds. There is one timeout for
+    /// the whole call, not one for each channel. UHD sends the same number of samples on every
+    /// channel, so if one channel cannot accept samples before the timeout, this function
+    /// returns with fewer samples sent on all channels. transmit_all() continues from that
+    /// point with a new timeout.
     ///
     /// On success, this function returns a transmitMetadata object with information about
     /// the number of samples actually transmitd.
+    ///
+    /// If all buffers are empty, this function returns metadata with zero samples immediately,
+    /// without calling UHD.
     pub fn transmit(
         &mut self,
         buffers: &mut [&[I]],
         timeout: f64,
     ) -> Result<TransmitMetadata, Error> {
-        let mut metadata = TransmitMetadata::default();
-        let mut samples_transmitted = 0usize;
+        self.transmit_with_metadata(buffers, TransmitMetadata::new()?, timeout)
+    }
 
-        // Initialize buffer_pointers
+    /// Transmits samples from the USRP, using the provided metadata to set the transmit time
+    /// and the burst flags
+    ///
+    /// The buffers and timeout are the same as for transmit(). On success, this function
+    /// returns the provided metadata with the number of samples actually transmitted.
+    ///
+    /// Empty buffers are sent to UHD only if the metadata has the end of burst flag set,
+    /// because UHD uses a packet with no samples to end a burst. Otherwise, this function
+    /// returns the metadata with zero samples without calling UHD.
+    pub fn transmit_with_metadata(
+        &mut self,
+        buffers: &mut [&[I]],
+        mut metadata: TransmitMetadata,
+        timeout: f64,
+    ) -> Result<TransmitMetadata, Error> {
+        // Initialize buffer_pointers, and check the number of buffers before skipping empty
+        // sends so that a wrong number of buffers is always reported
         if self.buffer_pointers.is_empty() {
             self.buffer_pointers
-                .resize(self.num_channels(), ptr::null_mut());
+                .resize(self.channels.len(), ptr::null_mut());
         }
-        // Now buffer_pointers.len() is equal to self.num_channels().
         assert_eq!(
             buffers.len(),
             self.buffer_pointers.len(),
-            "Number of buffers is not equal to this streamer's number of channels"
+            "Streamer configured for channels {:?} but got {} buffer(s)",
+            self.channels,
+            buffers.len()
         );
+        if buffers.iter().all(|buffer| buffer.is_empty()) && !metadata.end_of_burst() {
+            metadata.set_samples(0);
+            return Ok(metadata);
+        }
+
+        let mut samples_transmitted = 0usize;
         // Check that all buffers have the same length
-        let buffer_length = check_equal_buffer_lengths(buffers);
+        let buffer_length = c_buffer_length(check_equal_buffer_lengths(buffers))?;
 
         // Copy buffer pointers into C-compatible form
         for (entry, buffer) in self.buffer_pointers.iter_mut().zip(buffers.iter_mut()) {
@@ -105,7 +215,7 @@ impl<I> TransmitStreamer<'_, I> {
             uhd_sys::uhd_tx_streamer_send(
                 self.handle,
                 self.buffer_pointers.as_mut_ptr(),
-                buffer_length as _,
+                buffer_length,
                 metadata.handle_mut(),
                 timeout,
                 &mut samples_transmitted as *mut usize as *mut _,
@@ -116,11 +226,338 @@ impl<I> TransmitStreamer<'_, I> {
         Ok(metadata)
     }
 
+    /// Transmits all the provided samples, calling transmit() as many times as needed
+    ///
+    /// The buffers and timeout are the same as for transmit(), except that the buffers are
+    /// checked before each call and unequal lengths are reported as
+    /// Error::UnequalBufferLengths instead of a panic. This function returns Error::Timeout
+    /// if a call to transmit() sends no samples.
+    ///
+    /// Each call to transmit() has its own timeout, so a channel that stalls briefly only
+    /// delays the call in progress. The timeout is reached only if a whole call makes no
+    /// progress.
+    ///
+    /// On success, this function returns the number of samples transmitted on each channel.
+    pub fn transmit_all(&mut self, buffers: &[&[I]], timeout: f64) -> Result<usize, Error> {
+        let mut offset = 0;
+        loop {
+            let mut remaining = remaining_samples(buffers, offset)?;
+            if remaining.iter().all(|buffer| buffer.is_empty()) {
+                return Ok(offset);
+            }
+            let metadata = self.transmit(&mut remaining, timeout)?;
+            if metadata.samples() == 0 {
+                return Err(Error::Timeout);
+            }
+            offset += metadata.samples();
+        }
+    }
+
+    /// Reads samples from a reader and transmits them on a single channel as one burst
+    ///
+    /// The samples must be in the layout that FileSample describes. They are transmitted in
+    /// chunks of max_num_samps() samples. The first chunk starts the burst, and the last
+    /// chunk (which may be shorter) ends it. If the reader has no samples, nothing is
+    /// transmitted.
+    ///
+    /// timeout: The timeout for each call to transmit(), in seconds. This function returns
+    /// Error::Timeout if a call sends no samples.
+    ///
+    /// This function returns an I/O error with kind UnexpectedEof if the reader ends in the
+    /// middle of a sample. On success, it returns the number of samples transmitted. This
+    /// function panics if this streamer has more than one channel.
+    pub fn transmit_from<R>(&mut self, reader: &mut R, timeout: f64) -> Result<usize, Error>
+    where
+        I: FileSample + Clone + Default,
+        R: Read,
+    {
+        let chunk_bytes = self.max_num_samps()? * I::SIZE;
+        let mut current = vec![0u8; chunk_bytes];
+        let mut next = vec![0u8; chunk_bytes];
+        let mut current_length = read_samples(reader, &mut current, I::SIZE)?;
+        if current_length == 0 {
+            return Ok(0);
+        }
+        let mut samples = Vec::with_capacity(chunk_bytes / I::SIZE);
+        let mut transmitted = 0;
+        loop {
+            // Read ahead to find out if this is the last chunk
+            let next_length = if current_length == chunk_bytes {
+                read_samples(reader, &mut next, I::SIZE)?
+            } else {
+                0
+            };
+            let last = next_length == 0;
+
+            samples.clear();
+            samples.extend(
+                current[..current_length]
+                    .chunks_exact(I::SIZE)
+                    .map(I::read_bytes),
+            );
+            transmitted += send_burst(
+                &[&samples],
+                samples.len(),
+                transmitted == 0,
+                last,
+                |chunk_buffers, sob, eob| {
+                    let metadata = TransmitMetadata::with_options(None, sob, eob)?;
+                    self.transmit_with_metadata(chunk_buffers, metadata, timeout)
+                        .map(|metadata| metadata.samples())
+                },
+            )?;
+
+            if last {
+                return Ok(transmitted);
+            }
+            mem::swap(&mut current, &mut next);
+            current_length = next_length;
+        }
+    }
+
+    /// Transmits the provided samples as one burst, and then waits for the USRP to confirm
+    /// that the burst was transmitted without an underflow
+    ///
+    /// The buffers and timeout are the same as for transmit(). The timeout also applies to
+    /// waiting for each confirmation message from the USRP.
+    ///
+    /// This function returns Error::Underflow if the USRP reports an underflow during the
+    /// burst, Error::LateCommand if the burst time had already passed when it reached the
+    /// USRP, or Error::Timeout if the USRP does not confirm the end of the burst on every
+    /// channel.
+    pub fn transmit_burst_checked(
+        &mut self,
+        buffers: &mut [&[I]],
+        timeout: f64,
+    ) -> Result<TransmitMetadata, Error> {
+        // Discard any messages about earlier bursts
+        while self.receive_async_message(0.0)?.is_some() {}
+
+        // Send until all samples have been transmitted. Only the first send starts the burst,
+        // and only the send of the last chunk ends it.
+        check_equal_buffer_lengths(buffers);
+        let chunk = self.max_num_samps()?;
+        let mut last_metadata = None;
+        let transmitted = send_burst(buffers, chunk, true, true, |chunk_buffers, sob, eob| {
+            let metadata = TransmitMetadata::with_options(None, sob, eob)?;
+            let metadata = self.transmit_with_metadata(chunk_buffers, metadata, timeout)?;
+            let sent = metadata.samples();
+            last_metadata = Some(metadata);
+            Ok(sent)
+        })?;
+        let mut metadata = match last_metadata {
+            Some(metadata) => metadata,
+            None => TransmitMetadata::new()?,
+        };
+        metadata.set_samples(transmitted);
+
+        self.wait_for_burst_ack(timeout)?;
+        Ok(metadata)
+    }
+
+    /// Waits until every channel has acknowledged the end of a burst
+    ///
+    /// This function returns an error if an underflow or a late burst is reported first.
+    fn wait_for_burst_ack(&mut self, timeout: f64) -> Result<(), Error> {
+        let mut acknowledged = vec![false; self.num_channels()];
+        while !acknowledged.iter().all(|ack| *ack) {
+            let message = self.receive_async_message(timeout)?.ok_or(Error::Timeout)?;
+            match message.event()? {
+                AsyncEvent::BurstAck => {
+                    if let Some(ack) = acknowledged.get_mut(message.channel()?) {
+                        *ack = true;
+                    }
+                }
+                AsyncEvent::Underflow | AsyncEvent::UnderflowInPacket => {
+                    return Err(Error::Underflow)
+                }
+                AsyncEvent::TimeError => return Err(Error::LateCommand),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Receives a message that the USRP sent about an earlier transmit operation
+    ///
+    /// timeout: The maximum time to wait for a message, in seconds
+    ///
+    /// This function returns None if no message was received before the timeout.
+    pub fn receive_async_message(&mut self, timeout: f64) -> Result<Option<AsyncMetadata>, Error> {
+        let mut metadata = AsyncMetadata::new()?;
+        let mut valid = false;
+        check_status(unsafe {
+            uhd_sys::uhd_tx_streamer_recv_async_msg(
+                self.handle,
+                metadata.handle_mut(),
+                timeout,
+                &mut valid,
+            )
+        })?;
+        Ok(if valid { Some(metadata) } else { None })
+    }
+
+    /// Returns an iterator over the messages that the USRP has sent about earlier transmit
+    /// operations
+    ///
+    /// The iterator calls receive_async_message() with a timeout of 0.1 seconds, and ends when
+    /// no message arrives before the timeout. If receiving a message fails, the iterator yields
+    /// the error and then ends. This can be used after a burst to collect all of its events.
+    pub fn async_messages(&mut self) -> AsyncMessages<'_, 'usrp, I> {
+        AsyncMessages {
+            streamer: self,
+            done: false,
+        }
+    }
+
     /// transmits samples on a single channel with a timeout of 0.1 seconds and
     /// one_packet disabled
     pub fn transmit_simple(&mut self, buffer: &mut [I]) -> Result<TransmitMetadata, Error> {
         self.transmit(&mut [buffer], 0.1)
     }
+
+    /// Transmits samples on a single channel with the provided timeout, in seconds
+    pub fn transmit_simple_with(
+        &mut self,
+        buffer: &[I],
+        timeout: f64,
+    ) -> Result<TransmitMetadata, Error> {
+        self.transmit(&mut [buffer], timeout)
+    }
+}
+
+/// Returns the samples after offset in each buffer
+///
+/// This function returns Error::UnequalBufferLengths if the buffers do not all have the same
+/// length after offset.
+fn remaining_samples<'b, I>(buffers: &[&'b [I]], offset: usize) -> Result<Vec<&'b [I]>, Error> {
+    let remaining: Vec<&[I]> = buffers
+        .iter()
+        .map(|buffer| buffer.get(offset..).unwrap_or(&[]))
+        .collect();
+    let lengths_equal = remaining
+        .windows(2)
+        .all(|pair| pair[0].len() == pair[1].len());
+    if lengths_equal {
+        Ok(remaining)
+    } else {
+        Err(Error::UnequalBufferLengths)
+    }
+}
+
+/// Sends the samples in buffers (one per channel) in chunks, as part of a burst, and returns
+/// the number of samples sent on each channel
+///
+/// send is called with the samples to send on each channel and the start of burst and end of
+/// burst flags, and returns the number of samples that it accepted. Each call offers at most
+/// chunk samples. If start_of_burst is true, only the first call starts the burst. If
+/// end_of_burst is true, only the call that offers the last sample ends it. When send accepts
+/// only part of the last chunk, the rest is offered again with end of burst set, because UHD
+/// sends the end of burst flag only with the last packet of a buffer that it sends completely.
+///
+/// If the buffers are empty, send is called once with empty buffers, so that the burst flags
+/// are still sent. This function returns Error::Timeout if send accepts no samples of a
+/// non-empty chunk.
+fn send_burst<I, F>(
+    buffers: &[&[I]],
+    chunk: usize,
+    start_of_burst: bool,
+    end_of_burst: bool,
+    mut send: F,
+) -> Result<usize, Error>
+where
+    F: FnMut(&mut [&[I]], bool, bool) -> Result<usize, Error>,
+{
+    let total = buffers.first().map(|buffer| buffer.len()).unwrap_or(0);
+    let chunk = chunk.max(1);
+    let mut offset = 0;
+    loop {
+        let end = total.min(offset + chunk);
+        let mut chunk_buffers: Vec<&[I]> =
+            buffers.iter().map(|buffer| &buffer[offset..end]).collect();
+        let sent = send(
+            &mut chunk_buffers,
+            start_of_burst && offset == 0,
+            end_of_burst && end == total,
+        )?;
+        offset += sent;
+        if offset >= total {
+            return Ok(offset);
+        }
+        if sent == 0 {
+            return Err(Error::Timeout);
+        }
+    }
+}
+
+/// An iterator over asynchronous messages from a transmit streamer
+///
+/// This is returned by TransmitStreamer::async_messages().
+pub struct AsyncMessages<'s, 'usrp, I> {
+    streamer: &'s mut TransmitStreamer<'usrp, I>,
+    /// True if a receive operation has timed out or failed
+    done: bool,
+}
+
+impl<I> Iterator for AsyncMessages<'_, '_, I> {
+    type Item = Result<AsyncMetadata, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        const TIMEOUT: f64 = 0.1;
+        if self.done {
+            return None;
+        }
+        let result = self.streamer.receive_async_message(TIMEOUT).transpose();
+        self.done = !matches!(result, Some(Ok(_)));
+        result
+    }
+}
+
+/// Reads from a reader until a buffer is full or the reader ends, and returns the number of
+/// bytes read
+///
+/// This function returns an error with kind UnexpectedEof if the number of bytes read is not
+/// a multiple of sample_size.
+fn read_samples<R: Read>(
+    reader: &mut R,
+    buffer: &mut [u8],
+    sample_size: usize,
+) -> io::Result<usize> {
+    let mut length = 0;
+    while length < buffer.len() {
+        match reader.read(&mut buffer[length..]) {
+            Ok(0) => break,
+            Ok(read) => length += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    if length % sample_size == 0 {
+        Ok(length)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "Input ends in the middle of a sample",
+        ))
+    }
+}
+
+impl<I> Streamer for TransmitStreamer<'_, I> {
+    type Item = I;
+
+    fn channels(&self) -> &[usize] {
+        TransmitStreamer::channels(self)
+    }
+
+    fn num_channels(&self) -> usize {
+        TransmitStreamer::num_channels(self)
+    }
+
+    /// Returns Error::NotImplemented, because UHD does not accept stream commands on transmit
+    /// streams
+    fn send_command(&mut self, _command: &StreamCommand) -> Result<(), Error> {
+        Err(Error::NotImplemented)
+    }
 }
 
 impl<I> Drop for TransmitStreamer<'_, I> {
@@ -135,3 +572,105 @@ impl<I> Drop for TransmitStreamer<'_, I> {
 // self, which enforces single-thread access.
 unsafe impl<I> Send for TransmitStreamer<'_, I> {}
 unsafe impl<I> Sync for TransmitStreamer<'_, I> {}
+
+#[cfg(test)]
+mod test {
+    use super::{read_samples, remaining_samples, send_burst, TransmitStreamer};
+    use crate::Error;
+    use std::io::{self, Read};
+
+    /// A reader that returns at most three bytes from each read
+    struct SlowReader<'a>(&'a [u8]);
+
+    impl Read for SlowReader<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            let length = buffer.len().min(self.0.len()).min(3);
+            buffer[..length].copy_from_slice(&self.0[..length]);
+            self.0 = &self.0[length..];
+            Ok(length)
+        }
+    }
+
+    #[test]
+    fn read_samples_fills_buffer() {
+        let data: Vec<u8> = (0..10).collect();
+        let mut reader = SlowReader(&data);
+        let mut buffer = [0u8; 8];
+        assert_eq!(read_samples(&mut reader, &mut buffer, 4).unwrap(), 8);
+        assert_eq!(buffer, [0, 1, 2, 3, 4, 5, 6, 7]);
+        // Two bytes are left, which is half of a sample
+        let error = read_samples(&mut reader, &mut buffer, 4).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(read_samples(&mut reader, &mut buffer, 4).unwrap(), 0);
+    }
+
+    #[test]
+    fn empty_transmit_does_nothing() {
+        // transmit() creates its metadata with UHD, but the streamer handle is null, so this
+        // would fail if it called uhd_tx_streamer_send()
+        let mut streamer = TransmitStreamer::<i16>::new(&[0, 1], 1e6);
+        assert_eq!(
+            streamer.transmit(&mut [&[], &[]], 0.1).unwrap().samples(),
+            0
+        );
+    }
+
+    #[test]
+    fn burst_flags_set_once() {
+        let first: Vec<i16> = (0..8).collect();
+        let second: Vec<i16> = (8..16).collect();
+        let mut sent: Vec<Vec<i16>> = vec![Vec::new(), Vec::new()];
+        let mut starts = 0;
+        let mut ends = 0;
+        // Accepts at most 3 samples of each chunk of 4, so the first two sends are partial
+        let transmitted = send_burst(&[&first, &second], 4, true, true, |buffers, sob, eob| {
+            let accepted = buffers[0].len().min(3);
+            for (sent, buffer) in sent.iter_mut().zip(buffers.iter()) {
+                sent.extend_from_slice(&buffer[..accepted]);
+            }
+            if sob {
+                starts += 1;
+            }
+            if eob {
+                ends += 1;
+                // The end of burst must come with the last sample
+                assert_eq!(sent[0].last(), Some(&7));
+            }
+            Ok(accepted)
+        })
+        .unwrap();
+        assert_eq!(transmitted, 8);
+        assert_eq!(sent, vec![first, second]);
+        assert_eq!(starts, 1);
+        assert_eq!(ends, 1);
+    }
+
+    #[test]
+    fn empty_burst_sends_flags() {
+        let mut calls = Vec::new();
+        let transmitted = send_burst::<i16, _>(&[&[]], 4, true, true, |buffers, sob, eob| {
+            calls.push((buffers[0].len(), sob, eob));
+            Ok(0)
+        })
+        .unwrap();
+        assert_eq!(transmitted, 0);
+        assert_eq!(calls, vec![(0, true, true)]);
+    }
+
+    #[test]
+    fn remaining_samples_checks_lengths() {
+        let first = [1, 2, 3, 4];
+        let second = [5, 6, 7, 8];
+        let short = [9, 10, 11];
+
+        let remaining = remaining_samples(&[&first, &second], 3).unwrap();
+        assert_eq!(remaining, vec![&[4][..], &[8][..]]);
+        let remaining = remaining_samples(&[&first, &second], 4).unwrap();
+        assert!(remaining.iter().all(|buffer| buffer.is_empty()));
+
+        assert!(matches!(
+            remaining_samples(&[&first, &short], 1),
+            Err(Error::UnequalBufferLengths)
+        ));
+    }
+}
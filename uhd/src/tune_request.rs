@@ -1,5 +1,11 @@
+use std::convert::TryFrom;
+
+use crate::error::Error;
+
 /// A request to tune a frontend
-#[derive(Debug, Clone)]
+///
+/// The same request can be cloned and used to tune several channels.
+#[derive(Debug, Clone, PartialEq)]
 pub struct TuneRequest {
     pub(crate) target_frequency: f64,
     pub(crate) rf: TuneRequestPolicy,
@@ -30,6 +36,22 @@ impl TuneRequest {
         }
     }
 
+    /// Creates a tune request that keeps the current RF frontend frequency and sets only the
+    /// DSP frequency shift
+    ///
+    /// UHD does not provide a separate function to tune the DSP. This request moves the center
+    /// frequency within the currently captured band without retuning (and waiting for) the RF
+    /// local oscillator. The actual shift is available from TuneResult::actual_dsp_freq().
+    pub fn dsp_only(dsp_frequency: f64) -> Self {
+        TuneRequest {
+            // Not used because neither policy is Auto
+            target_frequency: 0.0,
+            rf: TuneRequestPolicy::None,
+            dsp: TuneRequestPolicy::Manual(dsp_frequency),
+            args: String::new(),
+        }
+    }
+
     /// Sets the policy for tuning the RF frontend
     pub fn set_rf_policy(&mut self, policy: TuneRequestPolicy) {
         self.rf = policy
@@ -39,13 +61,22 @@ impl TuneRequest {
         self.dsp = policy
     }
     /// Sets additional device-specific arguments
+    ///
+    /// The arguments are passed to UHD with the request when it is used with
+    /// Usrp::set_rx_frequency() or Usrp::set_tx_frequency(). For example, `mode_n=integer`
+    /// selects integer-N tuning on synthesizers that support it, which can reduce phase noise.
     pub fn set_args(&mut self, args: String) {
         self.args = args
     }
+
+    /// Returns the additional device-specific arguments
+    pub fn args(&self) -> &str {
+        &self.args
+    }
 }
 
 /// Policies for how tuning should be accomplished
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum TuneRequestPolicy {
     /// Keep the current value
     None,
@@ -73,3 +104,45 @@ impl TuneRequestPolicy {
         }
     }
 }
+
+impl TryFrom<&str> for TuneRequestPolicy {
+    type Error = Error;
+
+    /// Parses a policy from `none`, `auto`, or `manual:` followed by a frequency in hertz
+    /// (for example, `manual:915e6`)
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let unknown = || Error::UnknownVariant {
+            value: value.to_owned(),
+            expected: "none, auto, manual:<frequency>",
+        };
+        match value {
+            "none" => Ok(TuneRequestPolicy::None),
+            "auto" => Ok(TuneRequestPolicy::Auto),
+            _ => {
+                let frequency = value.strip_prefix("manual:").ok_or_else(unknown)?;
+                let frequency = frequency.trim().parse().map_err(|_| unknown())?;
+                Ok(TuneRequestPolicy::Manual(frequency))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TuneRequestPolicy;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn parse_policy() {
+        assert!(matches!(
+            TuneRequestPolicy::try_from("auto"),
+            Ok(TuneRequestPolicy::Auto)
+        ));
+        match TuneRequestPolicy::try_from("manual:915e6") {
+            Ok(TuneRequestPolicy::Manual(frequency)) => assert_eq!(frequency, 915e6),
+            other => panic!("Unexpected result {:?}", other),
+        }
+        assert!(TuneRequestPolicy::try_from("manual:").is_err());
+        assert!(TuneRequestPolicy::try_from("Auto").is_err());
+    }
+}
@@ -1,4 +1,8 @@
 /// The result of a tuning operation
+///
+/// Usrp::set_rx_frequency() and Usrp::set_tx_frequency() return all the frequencies that UHD
+/// reports. When a tune request splits the frequency between the RF front end and the DSP,
+/// actual_rf_freq() and actual_dsp_freq() give the two parts that were actually applied.
 #[derive(Clone)]
 pub struct TuneResult(uhd_sys::uhd_tune_result_t);
 
@@ -13,7 +17,10 @@ impl TuneResult {
         self.0.clipped_rf_freq
     }
 
-    /// Returns the actual RF frequency
+    /// Returns the actual RF frequency, in hertz, that the front end was tuned to
+    ///
+    /// This may differ from the clipped RF frequency because of the resolution of the
+    /// synthesizer.
     pub fn actual_rf_freq(&self) -> f64 {
         self.0.actual_rf_freq
     }
@@ -23,7 +30,11 @@ impl TuneResult {
         self.0.target_dsp_freq
     }
 
-    /// Returns the actual DSP frequency adjustment
+    /// Returns the actual DSP frequency adjustment, in hertz
+    ///
+    /// This is the frequency shift that the DSP applies to move the signal from the actual RF
+    /// frequency to the requested frequency. It may differ from the target DSP frequency because
+    /// of the resolution of the DSP.
     pub fn actual_dsp_freq(&self) -> f64 {
         self.0.actual_dsp_freq
     }
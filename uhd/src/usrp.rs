@@ -1,21 +1,38 @@
 use crate::{
-    error::{check_status, Error},
+    device_config::{ChannelConfig, DeviceConfig, MotherboardConfig},
+    error::{check_status, is_image_mismatch, is_unsupported_format, last_error_message, Error},
     motherboard_eeprom::MotherboardEeprom,
-    range::MetaRange,
+    range::{MetaRange, Range},
+    sensor::SensorValueHandle,
     stream::{Item, StreamArgs, StreamArgsC},
     string_vector::StringVector,
+    subdev_spec::SubdevSpecHandle,
     utils::copy_string,
-    DaughterBoardEeprom, ReceiveInfo, ReceiveStreamer, TimeSpec, TransmitInfo, TransmitStreamer,
-    TuneRequest, TuneResult,
+    Channel, ClockSync, DaughterBoardEeprom, FrontendCorrections, LoChain, LoChainEntry,
+    ReceiveInfo, ReceiveStreamer, SensorValue, SubdevSpec, TimeSpec, TransmitInfo,
+    TransmitStreamer, TuneRequest, TuneRequestPolicy, TuneResult,
 };
 
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::ffi::CString;
+use std::mem::ManuallyDrop;
 use std::ptr;
+use std::thread;
+use std::time::{Duration, Instant};
 /// A connection to a USRP device
 pub struct Usrp(uhd_sys::uhd_usrp_handle);
 
 impl Usrp {
+    /// A motherboard index that applies a setting to all motherboards
+    pub const ALL_MBOARDS: usize = usize::MAX;
+
+    /// A timeout for locking to a newly selected reference clock, for use with
+    /// configure_clock_sync() and wait_ref_locked()
+    ///
+    /// The PLLs of most devices lock within a few hundred milliseconds.
+    pub const REF_LOCK_TIMEOUT: Duration = Duration::from_secs(1);
+
     pub fn find(args: &str) -> Result<Vec<String>, Error> {
         let args = CString::new(args)?;
         let mut addresses = StringVector::new()?;
@@ -33,13 +50,75 @@ impl Usrp {
     /// * `addr`: The IP address of the USRP
     /// * `type`: The type of the USRP (allowed values include `usrp2` and others)
     ///
+    /// If the firmware or FPGA image on the device does not match the UHD version, this
+    /// function returns Error::ImageMismatch.
     pub fn open(args: &str) -> Result<Self, Error> {
         let mut handle: uhd_sys::uhd_usrp_handle = ptr::null_mut();
         let args_c = CString::new(args)?;
-        check_status(unsafe { uhd_sys::uhd_usrp_make(&mut handle, args_c.as_ptr()) })?;
+        check_status(unsafe { uhd_sys::uhd_usrp_make(&mut handle, args_c.as_ptr()) }).map_err(
+            |e| match last_error_message() {
+                Some(detail) if is_image_mismatch(&detail) => Error::ImageMismatch { detail },
+                _ => e,
+            },
+        )?;
         Ok(Usrp(handle))
     }
 
+    /// Opens a connection to a USRP, retrying if the device is temporarily unavailable
+    ///
+    /// A device is often briefly busy or not found just after another process closes it. If
+    /// open() fails with a transport or lookup error (Error::Io, Error::Usb, Error::Runtime,
+    /// Error::Key, or Error::Lookup), this function waits and tries again. The first wait is
+    /// backoff, and each later wait is twice as long as the previous one.
+    ///
+    /// attempts: The maximum number of times to call open(). A value of 0 is treated as 1.
+    ///
+    /// Other errors are returned immediately. If all attempts fail, this function returns the
+    /// error from the last attempt.
+    pub fn open_with_retry(args: &str, attempts: usize, backoff: Duration) -> Result<Self, Error> {
+        let mut delay = backoff;
+        let mut attempt = 1;
+        loop {
+            match Usrp::open(args) {
+                Err(Error::Io | Error::Usb | Error::Runtime | Error::Key | Error::Lookup)
+                    if attempt < attempts =>
+                {
+                    thread::sleep(delay);
+                    delay *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Closes the connection to this USRP
+    ///
+    /// Dropping a Usrp also closes the connection, but ignores errors. This function can be
+    /// used to close the connection at a known point (for example, before starting another
+    /// process that opens the same device) and to find out if closing failed.
+    ///
+    /// Streamers and other values that borrow this Usrp must be dropped first. The borrow
+    /// checker enforces this, because this function takes the Usrp by value:
+    ///
+    /// ```compile_fail
+    /// # use num_complex::Complex32;
+    /// # use uhd::{StreamArgs, Usrp};
+    /// # fn main() -> Result<(), uhd::Error> {
+    /// let usrp = Usrp::open("")?;
+    /// let streamer = usrp.get_rx_stream(&StreamArgs::<Complex32>::new("sc16"))?;
+    /// // Error: usrp is still borrowed by streamer
+    /// usrp.close()?;
+    /// drop(streamer);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn close(self) -> Result<(), Error> {
+        // Prevent drop() from freeing the handle again
+        let mut usrp = ManuallyDrop::new(self);
+        check_status(unsafe { uhd_sys::uhd_usrp_free(&mut usrp.0) })
+    }
+
     /// Returns the antennas available for transmission
     pub fn get_tx_antennas(&self, channel: usize) -> Result<Vec<String>, Error> {
         let mut vector = StringVector::new()?;
@@ -50,6 +129,8 @@ impl Usrp {
     }
 
     /// Returns the selected antenna for transmission
+    ///
+    /// The returned name is one of the names that get_tx_antennas() returns.
     pub fn get_tx_antenna(&self, channel: usize) -> Result<String, Error> {
         copy_string(|buffer, length| unsafe {
             uhd_sys::uhd_usrp_get_tx_antenna(self.0, channel as _, buffer, length as _)
@@ -66,6 +147,8 @@ impl Usrp {
     }
 
     /// Returns the selected antenna for receiving
+    ///
+    /// The returned name is one of the names that get_rx_antennas() returns.
     pub fn get_rx_antenna(&self, channel: usize) -> Result<String, Error> {
         copy_string(|buffer, length| unsafe {
             uhd_sys::uhd_usrp_get_rx_antenna(self.0, channel as _, buffer, length as _)
@@ -73,6 +156,9 @@ impl Usrp {
     }
 
     /// Returns the current receive front-end bandwidth
+    ///
+    /// This is the bandwidth that the analog filters are actually set to, which may be different
+    /// from the bandwidth last requested with set_rx_bandwidth().
     pub fn get_rx_bandwidth(&self, channel: usize) -> Result<f64, Error> {
         let mut value = 0.0;
         check_status(unsafe {
@@ -82,6 +168,10 @@ impl Usrp {
     }
 
     /// Returns the supported range of receive front-end bandwidth
+    ///
+    /// On some devices, only a few discrete bandwidths are available. The step of each range
+    /// in the returned meta-range, and MetaRange::clip(), can be used to find a bandwidth that
+    /// the device supports exactly.
     pub fn get_rx_bandwidth_range(&self, channel: usize) -> Result<MetaRange, Error> {
         let mut range = MetaRange::default();
         check_status(unsafe {
@@ -91,6 +181,9 @@ impl Usrp {
     }
 
     /// Returns the current transmit front-end bandwidth
+    ///
+    /// This is the bandwidth that the analog filters are actually set to, which may be different
+    /// from the bandwidth last requested with set_tx_bandwidth().
     pub fn get_tx_bandwidth(&self, channel: usize) -> Result<f64, Error> {
         let mut value = 0.0;
         check_status(unsafe {
@@ -100,6 +193,10 @@ impl Usrp {
     }
 
     /// Returns the supported range of transmit front-end bandwidth
+    ///
+    /// On some devices, only a few discrete bandwidths are available. The step of each range
+    /// in the returned meta-range, and MetaRange::clip(), can be used to find a bandwidth that
+    /// the device supports exactly.
     pub fn get_tx_bandwidth_range(&self, channel: usize) -> Result<MetaRange, Error> {
         let mut range = MetaRange::default();
         check_status(unsafe {
@@ -108,7 +205,10 @@ impl Usrp {
         Ok(range)
     }
 
-    /// Returns the current receive frequency
+    /// Returns the current receive center frequency
+    ///
+    /// This is the frequency that the most recent tune request produced, including both the RF
+    /// and DSP parts.
     pub fn get_rx_frequency(&self, channel: usize) -> Result<f64, Error> {
         let mut value = 0.0;
         check_status(unsafe { uhd_sys::uhd_usrp_get_rx_freq(self.0, channel as _, &mut value) })?;
@@ -124,7 +224,10 @@ impl Usrp {
         Ok(range)
     }
 
-    /// Returns the current transmit frequency
+    /// Returns the current transmit center frequency
+    ///
+    /// This is the frequency that the most recent tune request produced, including both the RF
+    /// and DSP parts.
     pub fn get_tx_frequency(&self, channel: usize) -> Result<f64, Error> {
         let mut value = 0.0;
         check_status(unsafe { uhd_sys::uhd_usrp_get_tx_freq(self.0, channel as _, &mut value) })?;
@@ -149,6 +252,23 @@ impl Usrp {
         })?;
         Ok(value)
     }
+
+    /// Returns the overall gain of every receive channel, in channel index order
+    ///
+    /// If reading the gain of a channel fails, this function returns Error::OnChannel with
+    /// the index of that channel and the error.
+    pub fn get_rx_gains(&self) -> Result<Vec<f64>, Error> {
+        (0..self.get_num_rx_channels()?)
+            .map(|channel| {
+                self.get_rx_gain(channel, "")
+                    .map_err(|error| Error::OnChannel {
+                        channel,
+                        source: Box::new(error),
+                    })
+            })
+            .collect()
+    }
+
     /// Returns the names of controllable gain elements
     pub fn get_rx_gain_names(&self, channel: usize) -> Result<Vec<String>, Error> {
         let mut names = StringVector::new()?;
@@ -159,6 +279,11 @@ impl Usrp {
     }
 
     /// Returns the range(s) of gains for a gain element
+    ///
+    /// An empty name selects the overall gain. The step of each range in the returned
+    /// meta-range is the gain resolution, and UHD rounds a requested gain to that step without
+    /// reporting it. A sweep can use the step to visit only gains that the device supports,
+    /// or MetaRange::clip() to find the gain that a request will produce.
     pub fn get_rx_gain_range(&self, channel: usize, name: &str) -> Result<MetaRange, Error> {
         let name = CString::new(name)?;
         let mut range = MetaRange::default();
@@ -187,6 +312,11 @@ impl Usrp {
     }
 
     /// Returns the range(s) of gains for a gain element
+    ///
+    /// An empty name selects the overall gain. The step of each range in the returned
+    /// meta-range is the gain resolution, and UHD rounds a requested gain to that step without
+    /// reporting it. A sweep can use the step to visit only gains that the device supports,
+    /// or MetaRange::clip() to find the gain that a request will produce.
     pub fn get_tx_gain_range(&self, channel: usize, name: &str) -> Result<MetaRange, Error> {
         let name = CString::new(name)?;
         let mut range = MetaRange::default();
@@ -196,11 +326,65 @@ impl Usrp {
         Ok(range)
     }
 
-    /// Clears the command time (?), causing stream commands to be sent immediately
+    /// Clears the command time, causing commands to take effect immediately
     pub fn clear_command_time(&mut self, mboard: usize) -> Result<(), Error> {
         check_status(unsafe { uhd_sys::uhd_usrp_clear_command_time(self.0, mboard as _) })
     }
 
+    /// Returns an estimate of the range of DSP frequency offsets that a receive channel can use
+    /// without an RF retune, in hertz relative to the current RF frequency
+    ///
+    /// The DSP shifts the received band within the band that the ADC digitizes. UHD does not
+    /// report the range of the DSP through the C API, so this function estimates it as the
+    /// largest offset that keeps the whole band of the current sample rate inside both the
+    /// master clock rate of the channel's motherboard and the analog bandwidth of the channel.
+    /// The device may accept a somewhat wider or narrower range. A scanner can retune with
+    /// TuneRequest::dsp_only() while the offset from the current RF frequency stays in this
+    /// range, which is much faster than an RF retune.
+    ///
+    /// The returned meta-range contains one range centered on zero. It is empty (with start and
+    /// stop equal to zero) if the sample rate is as wide as the digitized band.
+    pub fn get_rx_dsp_freq_range(&self, channel: usize) -> Result<MetaRange, Error> {
+        let rate = self.get_rx_sample_rate(channel)?;
+        let mut band = self.get_master_clock_rate(self.rx_channel_mboard(channel)?)?;
+        let bandwidth = self.get_rx_bandwidth(channel)?;
+        if bandwidth > 0.0 {
+            band = band.min(bandwidth);
+        }
+        let half_span = ((band - rate) / 2.0).max(0.0);
+        let mut range = MetaRange::new();
+        range.push(Range::new(-half_span, half_span, 0.0));
+        Ok(range)
+    }
+
+    /// Returns the number of receive channels of each motherboard, from the receive subdevice
+    /// specifications
+    ///
+    /// UHD numbers the channels of motherboard 0 first, then the channels of motherboard 1,
+    /// and so on.
+    fn rx_channels_per_mboard(&self) -> Result<Vec<usize>, Error> {
+        (0..self.get_num_motherboards()?)
+            .map(|mboard| Ok(self.get_rx_subdev_spec(mboard)?.pairs().len()))
+            .collect()
+    }
+
+    /// Returns the motherboard that a receive channel belongs to, or Error::InvalidChannel if
+    /// the channel does not exist
+    fn rx_channel_mboard(&self, channel: usize) -> Result<usize, Error> {
+        let counts = self.rx_channels_per_mboard()?;
+        let mut first = 0;
+        for (mboard, count) in counts.iter().enumerate() {
+            if channel < first + count {
+                return Ok(mboard);
+            }
+            first += count;
+        }
+        Err(Error::InvalidChannel {
+            channel,
+            num_channels: first,
+        })
+    }
+
     /// Gets the ranges of front-end frequencies for a receive channel
     pub fn get_fe_rx_freq_range(&self, channel: usize) -> Result<MetaRange, Error> {
         let mut range = MetaRange::default();
@@ -228,6 +412,15 @@ impl Usrp {
         Ok(rate)
     }
 
+    /// Sets the frequency of the master clock
+    ///
+    /// UHD does not provide the range of valid master clock rates through its C API. On
+    /// devices with a variable master clock, the rate is coerced to the nearest supported
+    /// value, which get_master_clock_rate() returns. An unsupported rate returns an error.
+    pub fn set_master_clock_rate(&mut self, rate: f64, mboard: usize) -> Result<(), Error> {
+        check_status(unsafe { uhd_sys::uhd_usrp_set_master_clock_rate(self.0, rate, mboard as _) })
+    }
+
     /// Returns the name of the motherboard
     pub fn get_motherboard_name(&self, mboard: usize) -> Result<String, Error> {
         copy_string(|buffer, length| unsafe {
@@ -235,6 +428,26 @@ impl Usrp {
         })
     }
 
+    /// Returns the serial number of the motherboard, read from its EEPROM
+    ///
+    /// This function returns Error::Key if the EEPROM does not contain a serial number.
+    pub fn get_motherboard_serial(&self, mboard: usize) -> Result<String, Error> {
+        self.get_motherboard_eeprom(mboard)?
+            .get("serial")?
+            .ok_or(Error::Key)
+    }
+
+    /// Returns a human-readable summary of the device, including the motherboards,
+    /// daughterboards, and their serial numbers
+    ///
+    /// UHD does not provide the resolved device arguments through its C API. This summary
+    /// can be recorded instead to identify the device that was opened.
+    pub fn get_pp_string(&self) -> Result<String, Error> {
+        copy_string(|buffer, length| unsafe {
+            uhd_sys::uhd_usrp_get_pp_string(self.0, buffer, length as _)
+        })
+    }
+
     /// Returns the transmit gain, normalized to [0, 1]
     pub fn get_normalized_tx_gain(&self, channel: usize) -> Result<f64, Error> {
         let mut value = 0.0;
@@ -280,6 +493,18 @@ impl Usrp {
         Ok(value)
     }
 
+    /// Returns a receive channel with the provided index, or Error::InvalidChannel if the index
+    /// is not less than get_num_rx_channels()
+    pub fn rx_channel(&self, index: usize) -> Result<Channel, Error> {
+        Channel::checked(index, self.get_num_rx_channels()?)
+    }
+
+    /// Returns a transmit channel with the provided index, or Error::InvalidChannel if the
+    /// index is not less than get_num_tx_channels()
+    pub fn tx_channel(&self, index: usize) -> Result<Channel, Error> {
+        Channel::checked(index, self.get_num_tx_channels()?)
+    }
+
     /// Writes a user register on the USRP
     ///
     /// address: The address of the register
@@ -310,6 +535,20 @@ impl Usrp {
         })?;
         Ok(vector.into())
     }
+    /// Returns the current time source
+    pub fn get_time_source(&self, mboard: usize) -> Result<String, Error> {
+        copy_string(|buffer, length| unsafe {
+            uhd_sys::uhd_usrp_get_time_source(self.0, mboard as _, buffer, length as _)
+        })
+    }
+    /// Returns the available time sources
+    pub fn get_time_sources(&self, mboard: usize) -> Result<Vec<String>, Error> {
+        let mut vector = StringVector::new()?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_get_time_sources(self.0, mboard as _, vector.handle_mut())
+        })?;
+        Ok(vector.into())
+    }
     /// Returns the available sensors on the motherboard
     pub fn get_mboard_sensor_names(&self, mboard: usize) -> Result<Vec<String>, Error> {
         let mut vector = StringVector::new()?;
@@ -319,6 +558,176 @@ impl Usrp {
         Ok(vector.into())
     }
 
+    /// Returns true if a motherboard has a sensor with the provided name
+    ///
+    /// Sensors differ between devices (for example, a B200 without a GPSDO has no `gps_locked`
+    /// sensor), so this can be checked before calling get_mboard_sensor().
+    pub fn has_mboard_sensor(&self, name: &str, mboard: usize) -> Result<bool, Error> {
+        Ok(self
+            .get_mboard_sensor_names(mboard)?
+            .iter()
+            .any(|sensor| sensor == name))
+    }
+
+    /// Returns the current value of a sensor on the motherboard
+    pub fn get_mboard_sensor(&self, name: &str, mboard: usize) -> Result<SensorValue, Error> {
+        let name = CString::new(name)?;
+        let mut value = SensorValueHandle::new()?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_get_mboard_sensor(
+                self.0,
+                name.as_ptr(),
+                mboard as _,
+                value.handle_mut(),
+            )
+        })?;
+        value.value()
+    }
+
+    /// Returns the GPS time from the `gps_time` sensor of a motherboard, in whole seconds since
+    /// the Unix epoch
+    ///
+    /// This function returns an error if the motherboard does not have a GPS receiver, and
+    /// Error::Type if the sensor does not have an integer value.
+    pub fn get_gps_time(&self, mboard: usize) -> Result<TimeSpec, Error> {
+        match self.get_mboard_sensor("gps_time", mboard)? {
+            SensorValue::Int(seconds) => Ok(TimeSpec {
+                seconds,
+                fraction: 0.0,
+            }),
+            _ => Err(Error::Type),
+        }
+    }
+
+    /// Returns the most recent GPGGA sentence from the `gps_gpgga` sensor of a motherboard
+    ///
+    /// The sentence is returned as the raw NMEA string, which includes the fix quality, the
+    /// number of satellites, and the altitude. This function returns Error::MissingSensor if
+    /// the motherboard does not have a GPS receiver that reports GPGGA sentences, and
+    /// Error::Type if the sensor does not have a string value.
+    pub fn get_gps_gpgga(&self, mboard: usize) -> Result<String, Error> {
+        const SENSOR: &str = "gps_gpgga";
+        if !self.has_mboard_sensor(SENSOR, mboard)? {
+            return Err(Error::MissingSensor {
+                name: SENSOR.to_owned(),
+                mboard,
+            });
+        }
+        match self.get_mboard_sensor(SENSOR, mboard)? {
+            SensorValue::String(sentence) => Ok(sentence),
+            _ => Err(Error::Type),
+        }
+    }
+
+    /// Reads all sensors of a motherboard
+    ///
+    /// This returns a map from each name that get_mboard_sensor_names() returns to the value of
+    /// that sensor. If any sensor cannot be read, this function returns an error.
+    pub fn sensor_snapshot(&self, mboard: usize) -> Result<HashMap<String, SensorValue>, Error> {
+        self.get_mboard_sensor_names(mboard)?
+            .into_iter()
+            .map(|name| {
+                let value = self.get_mboard_sensor(&name, mboard)?;
+                Ok((name, value))
+            })
+            .collect()
+    }
+
+    /// Reads the current settings of all motherboards and channels
+    ///
+    /// The snapshot includes the clock and time sources, master clock rate, and sensor values of
+    /// each motherboard, and the frequency, sample rate, overall gain, antenna, and bandwidth of
+    /// each receive and transmit channel. It can be restored with apply_config().
+    pub fn dump_config(&self) -> Result<DeviceConfig, Error> {
+        let motherboards = (0..self.get_num_motherboards()?)
+            .map(|mboard| {
+                Ok(MotherboardConfig {
+                    clock_source: self.get_clock_source(mboard)?,
+                    time_source: self.get_time_source(mboard)?,
+                    master_clock_rate: self.get_master_clock_rate(mboard)?,
+                    sensors: self.sensor_snapshot(mboard)?.into_iter().collect(),
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        let rx_channels = (0..self.get_num_rx_channels()?)
+            .map(|channel| {
+                Ok(ChannelConfig {
+                    frequency: self.get_rx_frequency(channel)?,
+                    sample_rate: self.get_rx_sample_rate(channel)?,
+                    gain: self.get_rx_gain(channel, "")?,
+                    antenna: self.get_rx_antenna(channel)?,
+                    bandwidth: self.get_rx_bandwidth(channel)?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        let tx_channels = (0..self.get_num_tx_channels()?)
+            .map(|channel| {
+                Ok(ChannelConfig {
+                    frequency: self.get_tx_frequency(channel)?,
+                    sample_rate: self.get_tx_sample_rate(channel)?,
+                    gain: self.get_tx_gain(channel, "")?,
+                    antenna: self.get_tx_antenna(channel)?,
+                    bandwidth: self.get_tx_bandwidth(channel)?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(DeviceConfig {
+            motherboards,
+            rx_channels,
+            tx_channels,
+        })
+    }
+
+    /// Applies settings from a snapshot that dump_config() created
+    ///
+    /// The settings are applied in an order that keeps later settings valid: first the clock
+    /// sources, time sources, and master clock rates of the motherboards, then the sample rates
+    /// of the channels (which depend on the master clock rate), and then the antenna,
+    /// bandwidth, frequency, and gain of each channel. Sensor values are not applied.
+    ///
+    /// This function returns Error::Index if the configuration has more motherboards or
+    /// channels than this USRP. If applying a setting fails, the settings before it remain
+    /// applied.
+    pub fn apply_config(&mut self, config: &DeviceConfig) -> Result<(), Error> {
+        if config.motherboards.len() > self.get_num_motherboards()?
+            || config.rx_channels.len() > self.get_num_rx_channels()?
+            || config.tx_channels.len() > self.get_num_tx_channels()?
+        {
+            return Err(Error::Index);
+        }
+
+        for (mboard, mboard_config) in config.motherboards.iter().enumerate() {
+            self.set_clock_source(&mboard_config.clock_source, mboard)?;
+            self.set_time_source(&mboard_config.time_source, mboard)?;
+            self.set_master_clock_rate(mboard_config.master_clock_rate, mboard)?;
+        }
+        for (channel, channel_config) in config.rx_channels.iter().enumerate() {
+            self.set_rx_sample_rate(channel_config.sample_rate, channel)?;
+        }
+        for (channel, channel_config) in config.tx_channels.iter().enumerate() {
+            self.set_tx_sample_rate(channel_config.sample_rate, channel)?;
+        }
+        for (channel, channel_config) in config.rx_channels.iter().enumerate() {
+            self.set_rx_antenna(&channel_config.antenna, channel)?;
+            self.set_rx_bandwidth(channel_config.bandwidth, channel)?;
+            self.set_rx_frequency(
+                &TuneRequest::with_frequency(channel_config.frequency),
+                channel,
+            )?;
+            self.set_rx_gain(channel_config.gain, channel, "")?;
+        }
+        for (channel, channel_config) in config.tx_channels.iter().enumerate() {
+            self.set_tx_antenna(&channel_config.antenna, channel)?;
+            self.set_tx_bandwidth(channel_config.bandwidth, channel)?;
+            self.set_tx_frequency(
+                &TuneRequest::with_frequency(channel_config.frequency),
+                channel,
+            )?;
+            self.set_tx_gain(channel_config.gain, channel, "")?;
+        }
+        Ok(())
+    }
+
     /// Returns the values stored in the motherboard EEPROM
     pub fn get_motherboard_eeprom(&self, mboard: usize) -> Result<MotherboardEeprom, Error> {
         let mut eeprom = MotherboardEeprom::default();
@@ -406,6 +815,9 @@ impl Usrp {
     }
 
     /// Returns true if the provided local oscillator is exported
+    ///
+    /// When sharing a local oscillator between channels, this can be used to check that
+    /// set_rx_lo_export_enabled() took effect on the source channel.
     pub fn get_rx_lo_export_enabled(&self, channel: usize, name: &str) -> Result<bool, Error> {
         let name = CString::new(name)?;
         let mut enabled = false;
@@ -420,7 +832,56 @@ impl Usrp {
         Ok(enabled)
     }
 
+    /// Reads the sources and export settings of a local oscillator on several receive
+    /// channels, and checks that they form a valid chain
+    ///
+    /// In a valid chain, exactly one channel exports the local oscillator, and every other
+    /// channel uses a source other than `internal` (for example, `companion` or `external`)
+    /// to import it. A channel that uses its own synthesizer without exporting it is not
+    /// phase-coherent with the others. This is common with TwinRX daughterboards, where
+    /// name can be `all` or a specific stage such as `LO1`.
+    ///
+    /// This function returns the detected chain, or Error::InvalidLoChain if the chain is not
+    /// valid.
+    pub fn verify_lo_chain(&self, channels: &[usize], name: &str) -> Result<LoChain, Error> {
+        let sources = channels
+            .iter()
+            .map(|&channel| {
+                Ok(LoChainEntry {
+                    channel,
+                    source: self.get_rx_lo_source(channel, name)?,
+                    exported: self.get_rx_lo_export_enabled(channel, name)?,
+                })
+            })
+            .collect::<Result<Vec<LoChainEntry>, Error>>()?;
+
+        let exporters: Vec<usize> = sources
+            .iter()
+            .filter(|entry| entry.exported)
+            .map(|entry| entry.channel)
+            .collect();
+        let unshared: Vec<usize> = sources
+            .iter()
+            .filter(|entry| !entry.exported && entry.source == "internal")
+            .map(|entry| entry.channel)
+            .collect();
+        if exporters.len() == 1 && unshared.is_empty() {
+            Ok(LoChain {
+                exporter: exporters[0],
+                sources,
+            })
+        } else {
+            Err(Error::InvalidLoChain {
+                exporters,
+                unshared,
+            })
+        }
+    }
+
     /// Returns true if the provided local oscillator is exported
+    ///
+    /// When sharing a local oscillator between channels, this can be used to check that
+    /// set_tx_lo_export_enabled() took effect on the source channel.
     pub fn get_tx_lo_export_enabled(&self, channel: usize, name: &str) -> Result<bool, Error> {
         let name = CString::new(name)?;
         let mut enabled = false;
@@ -436,6 +897,9 @@ impl Usrp {
     }
 
     /// Returns the frequency of a local oscillator
+    ///
+    /// After a call to set_rx_lo_frequency(), this returns the frequency that the
+    /// synthesizer actually produces, which may differ from the requested frequency.
     pub fn get_rx_lo_frequency(&self, channel: usize, name: &str) -> Result<f64, Error> {
         let name = CString::new(name)?;
         let mut value = 0.0;
@@ -445,6 +909,54 @@ impl Usrp {
         Ok(value)
     }
 
+    /// Returns the range of frequencies that a local oscillator can produce
+    ///
+    /// Each LO stage has its own range, selected by name. The step of each range in the
+    /// returned meta-range is the synthesizer resolution, if the device reports one, and
+    /// MetaRange::clip() can be used to predict where a requested frequency will be placed.
+    pub fn get_rx_lo_freq_range(&self, channel: usize, name: &str) -> Result<MetaRange, Error> {
+        let name = CString::new(name)?;
+        let mut range = MetaRange::default();
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_get_rx_lo_freq_range(
+                self.0,
+                name.as_ptr(),
+                channel as _,
+                range.handle(),
+            )
+        })?;
+        Ok(range)
+    }
+
+    /// Returns the source of a local oscillator (for example, `internal` or `external`)
+    pub fn get_rx_lo_source(&self, channel: usize, name: &str) -> Result<String, Error> {
+        let name = CString::new(name)?;
+        copy_string(|buffer, length| unsafe {
+            uhd_sys::uhd_usrp_get_rx_lo_source(
+                self.0,
+                name.as_ptr(),
+                channel as _,
+                buffer,
+                length as _,
+            )
+        })
+    }
+
+    /// Returns the sources that a local oscillator can use
+    pub fn get_rx_lo_sources(&self, channel: usize, name: &str) -> Result<Vec<String>, Error> {
+        let name = CString::new(name)?;
+        let mut vector = StringVector::new()?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_get_rx_lo_sources(
+                self.0,
+                name.as_ptr(),
+                channel as _,
+                vector.handle_mut(),
+            )
+        })?;
+        Ok(vector.into())
+    }
+
     /// Returns the names of local oscillators
     pub fn get_rx_lo_names(&self, channel: usize) -> Result<Vec<String>, Error> {
         let mut vector = StringVector::new()?;
@@ -454,6 +966,27 @@ impl Usrp {
         Ok(vector.into())
     }
 
+    /// Returns the subdevice specification that maps receive channels to front ends on a
+    /// motherboard
+    pub fn get_rx_subdev_spec(&self, mboard: usize) -> Result<SubdevSpec, Error> {
+        let spec = SubdevSpecHandle::new(&SubdevSpec::default())?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_get_rx_subdev_spec(self.0, mboard as _, spec.handle())
+        })?;
+        spec.spec()
+    }
+
+    /// Returns the name of the receive front end that a channel uses
+    ///
+    /// This is a descriptive name from the daughterboard (for example, `SBX RX` or
+    /// `FE-RX2`), which can be easier to match to a physical port than the subdevice
+    /// specification.
+    pub fn get_rx_subdev_name(&self, channel: usize) -> Result<String, Error> {
+        copy_string(|buffer, length| unsafe {
+            uhd_sys::uhd_usrp_get_rx_subdev_name(self.0, channel as _, buffer, length as _)
+        })
+    }
+
     /// Returns the names of sensors that relate to receiving
     pub fn get_rx_sensor_names(&self, channel: usize) -> Result<Vec<String>, Error> {
         let mut vector = StringVector::new()?;
@@ -463,7 +996,20 @@ impl Usrp {
         Ok(vector.into())
     }
 
+    /// Returns the current value of a sensor that relates to receiving
+    pub fn get_rx_sensor(&self, name: &str, channel: usize) -> Result<SensorValue, Error> {
+        let name = CString::new(name)?;
+        let mut value = SensorValueHandle::new()?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_get_rx_sensor(self.0, name.as_ptr(), channel as _, value.handle_mut())
+        })?;
+        value.value()
+    }
+
     /// Returns the frequency of a local oscillator
+    ///
+    /// After a call to set_tx_lo_frequency(), this returns the frequency that the
+    /// synthesizer actually produces, which may differ from the requested frequency.
     pub fn get_tx_lo_frequency(&self, channel: usize, name: &str) -> Result<f64, Error> {
         let name = CString::new(name)?;
         let mut value = 0.0;
@@ -473,6 +1019,54 @@ impl Usrp {
         Ok(value)
     }
 
+    /// Returns the range of frequencies that a local oscillator can produce
+    ///
+    /// Each LO stage has its own range, selected by name. The step of each range in the
+    /// returned meta-range is the synthesizer resolution, if the device reports one, and
+    /// MetaRange::clip() can be used to predict where a requested frequency will be placed.
+    pub fn get_tx_lo_freq_range(&self, channel: usize, name: &str) -> Result<MetaRange, Error> {
+        let name = CString::new(name)?;
+        let mut range = MetaRange::default();
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_get_tx_lo_freq_range(
+                self.0,
+                name.as_ptr(),
+                channel as _,
+                range.handle(),
+            )
+        })?;
+        Ok(range)
+    }
+
+    /// Returns the source of a local oscillator (for example, `internal` or `external`)
+    pub fn get_tx_lo_source(&self, channel: usize, name: &str) -> Result<String, Error> {
+        let name = CString::new(name)?;
+        copy_string(|buffer, length| unsafe {
+            uhd_sys::uhd_usrp_get_tx_lo_source(
+                self.0,
+                name.as_ptr(),
+                channel as _,
+                buffer,
+                length as _,
+            )
+        })
+    }
+
+    /// Returns the sources that a local oscillator can use
+    pub fn get_tx_lo_sources(&self, channel: usize, name: &str) -> Result<Vec<String>, Error> {
+        let name = CString::new(name)?;
+        let mut vector = StringVector::new()?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_get_tx_lo_sources(
+                self.0,
+                name.as_ptr(),
+                channel as _,
+                vector.handle_mut(),
+            )
+        })?;
+        Ok(vector.into())
+    }
+
     /// Returns the names of local oscillators
     pub fn get_tx_lo_names(&self, channel: usize) -> Result<Vec<String>, Error> {
         let mut vector = StringVector::new()?;
@@ -482,6 +1076,27 @@ impl Usrp {
         Ok(vector.into())
     }
 
+    /// Returns the subdevice specification that maps transmit channels to front ends on a
+    /// motherboard
+    pub fn get_tx_subdev_spec(&self, mboard: usize) -> Result<SubdevSpec, Error> {
+        let spec = SubdevSpecHandle::new(&SubdevSpec::default())?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_get_tx_subdev_spec(self.0, mboard as _, spec.handle())
+        })?;
+        spec.spec()
+    }
+
+    /// Returns the name of the transmit front end that a channel uses
+    ///
+    /// This is a descriptive name from the daughterboard (for example, `SBX RX` or
+    /// `FE-RX2`), which can be easier to match to a physical port than the subdevice
+    /// specification.
+    pub fn get_tx_subdev_name(&self, channel: usize) -> Result<String, Error> {
+        copy_string(|buffer, length| unsafe {
+            uhd_sys::uhd_usrp_get_tx_subdev_name(self.0, channel as _, buffer, length as _)
+        })
+    }
+
     /// Returns the names of sensors that relate to transmitting
     pub fn get_tx_sensor_names(&self, channel: usize) -> Result<Vec<String>, Error> {
         let mut vector = StringVector::new()?;
@@ -491,11 +1106,70 @@ impl Usrp {
         Ok(vector.into())
     }
 
+    /// Returns the current value of a sensor that relates to transmitting
+    pub fn get_tx_sensor(&self, name: &str, channel: usize) -> Result<SensorValue, Error> {
+        let name = CString::new(name)?;
+        let mut value = SensorValueHandle::new()?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_get_tx_sensor(self.0, name.as_ptr(), channel as _, value.handle_mut())
+        })?;
+        value.value()
+    }
+
+    /// Returns the temperature near a transmit channel, in degrees Celsius
+    ///
+    /// This reads the `temp` sensor of the channel if it has one (for example, on N3xx
+    /// devices), and otherwise the `temp` sensor of motherboard 0 (for example, the RF
+    /// chip temperature on B2xx devices). This function returns Error::MissingSensor if
+    /// neither sensor exists, and Error::Type if the sensor value is not a number.
+    pub fn get_tx_temperature(&self, channel: usize) -> Result<f64, Error> {
+        const SENSOR: &str = "temp";
+        let value = if self
+            .get_tx_sensor_names(channel)?
+            .iter()
+            .any(|name| name == SENSOR)
+        {
+            self.get_tx_sensor(SENSOR, channel)?
+        } else if self.has_mboard_sensor(SENSOR, 0)? {
+            self.get_mboard_sensor(SENSOR, 0)?
+        } else {
+            return Err(Error::MissingSensor {
+                name: SENSOR.to_owned(),
+                mboard: 0,
+            });
+        };
+        match value {
+            SensorValue::Real(temperature) => Ok(temperature),
+            SensorValue::Int(temperature) => Ok(temperature as f64),
+            _ => Err(Error::Type),
+        }
+    }
+
     /// Opens a stream that can be used to receive samples
-    pub fn get_rx_stream<I>(
-        &mut self,
-        args: &StreamArgs<I>,
-    ) -> Result<ReceiveStreamer<'_, I>, Error>
+    ///
+    /// This is the only way to create a streamer. The streamer borrows this Usrp, so it cannot
+    /// outlive it. Because the borrow is shared, several streamers can be open at the same time,
+    /// and other settings can still be read while streaming.
+    ///
+    /// Using a streamer after its Usrp has been dropped does not compile:
+    ///
+    /// ```compile_fail
+    /// use num_complex::Complex32;
+    /// use uhd::{StreamArgs, Usrp};
+    ///
+    /// let streamer = {
+    ///     let usrp = Usrp::open("").unwrap();
+    ///     usrp.get_rx_stream(&StreamArgs::<Complex32>::new("sc16"))
+    ///         .unwrap()
+    /// };
+    /// drop(streamer);
+    /// ```
+    ///
+    /// This function returns an error if the stream arguments contain a null byte, or if UHD
+    /// cannot create the stream (for example, because a channel index is out of range). If the
+    /// device or UHD does not support the combination of host and wire formats, the error is
+    /// Error::UnsupportedFormat.
+    pub fn get_rx_stream<I>(&self, args: &StreamArgs<I>) -> Result<ReceiveStreamer<'_, I>, Error>
     where
         I: Item,
     {
@@ -515,22 +1189,47 @@ impl Usrp {
                 .expect("Number of channels too large"),
         };
 
-        // Create a streamer
-        let mut streamer = ReceiveStreamer::new();
+        // Create a streamer, remembering the current sample rate of its first channel
+        let rate = self.get_rx_sample_rate(args.channels.first().copied().unwrap_or(0))?;
+        let mut streamer = ReceiveStreamer::new(args.channels, rate);
         check_status(unsafe { uhd_sys::uhd_rx_streamer_make(streamer.handle_mut()) })?;
         // Associate streamer with USRP
         check_status(unsafe {
             uhd_sys::uhd_usrp_get_rx_stream(self.0, &mut args_c, streamer.handle())
-        })?;
+        })
+        .map_err(|e| stream_format_error(e, &args))?;
 
         Ok(streamer)
     }
 
+    /// Replaces a receive streamer with a new one that uses different arguments
+    ///
+    /// A streamer's format and channels cannot be changed after it is created. This function
+    /// frees the old streamer before creating the new one, so the device never has both
+    /// streams open at the same time.
+    pub fn recreate_rx_stream<'a, I, J>(
+        &'a self,
+        old: ReceiveStreamer<'a, I>,
+        args: &StreamArgs<J>,
+    ) -> Result<ReceiveStreamer<'a, J>, Error>
+    where
+        J: Item,
+    {
+        drop(old);
+        self.get_rx_stream(args)
+    }
+
     /// Opens a stream that can be used to transmit samples
-    pub fn get_tx_stream<I>(
-        &mut self,
-        args: &StreamArgs<I>,
-    ) -> Result<TransmitStreamer<'_, I>, Error>
+    ///
+    /// This is the only way to create a streamer. The streamer borrows this Usrp, so it cannot
+    /// outlive it. Because the borrow is shared, several streamers can be open at the same time,
+    /// and other settings can still be read while streaming.
+    ///
+    /// This function returns an error if the stream arguments contain a null byte, or if UHD
+    /// cannot create the stream (for example, because a channel index is out of range). If the
+    /// device or UHD does not support the combination of host and wire formats, the error is
+    /// Error::UnsupportedFormat.
+    pub fn get_tx_stream<I>(&self, args: &StreamArgs<I>) -> Result<TransmitStreamer<'_, I>, Error>
     where
         I: Item,
     {
@@ -550,17 +1249,56 @@ impl Usrp {
                 .expect("Number of channels too large"),
         };
 
-        // Create a streamer
-        let mut streamer = TransmitStreamer::new();
+        // Create a streamer, remembering the current sample rate of its first channel
+        let rate = self.get_tx_sample_rate(args.channels.first().copied().unwrap_or(0))?;
+        let mut streamer = TransmitStreamer::new(args.channels, rate);
         check_status(unsafe { uhd_sys::uhd_tx_streamer_make(streamer.handle_mut()) })?;
         // Associate streamer with USRP
         check_status(unsafe {
             uhd_sys::uhd_usrp_get_tx_stream(self.0, &mut args_c, streamer.handle())
-        })?;
+        })
+        .map_err(|e| stream_format_error(e, &args))?;
 
         Ok(streamer)
     }
 
+    /// Replaces a transmit streamer with a new one that uses different arguments
+    ///
+    /// A streamer's format and channels cannot be changed after it is created. This function
+    /// frees the old streamer before creating the new one, so the device never has both
+    /// streams open at the same time.
+    pub fn recreate_tx_stream<'a, I, J>(
+        &'a self,
+        old: TransmitStreamer<'a, I>,
+        args: &StreamArgs<J>,
+    ) -> Result<TransmitStreamer<'a, J>, Error>
+    where
+        J: Item,
+    {
+        drop(old);
+        self.get_tx_stream(args)
+    }
+
+    /// Opens one receive stream for each channel in the stream arguments
+    ///
+    /// Each streamer has the format and arguments from args and one of its channels, in the
+    /// same order. Because each streamer accepts its own stream commands, the channels can
+    /// be started and stopped independently (for example, with timed commands that start
+    /// each channel at a different time). With one streamer for all channels, a stream
+    /// command always applies to all of them.
+    pub fn get_rx_streams_per_channel<I>(
+        &self,
+        args: &StreamArgs<I>,
+    ) -> Result<Vec<ReceiveStreamer<'_, I>>, Error>
+    where
+        I: Item,
+    {
+        args.per_channel()
+            .iter()
+            .map(|channel_args| self.get_rx_stream(channel_args))
+            .collect()
+    }
+
     /// Returns the current receive sample rate in samples/second
     pub fn get_rx_sample_rate(&self, channel: usize) -> Result<f64, Error> {
         let mut value = 0.0;
@@ -568,7 +1306,9 @@ impl Usrp {
         Ok(value)
     }
 
-    /// Returns the ranges of supported sample rates
+    /// Returns the ranges of supported receive sample rates
+    ///
+    /// The supported transmit rates, from get_tx_sample_rates(), may be different.
     pub fn get_rx_sample_rates(&self, channel: usize) -> Result<MetaRange, Error> {
         let mut range = MetaRange::new();
         check_status(unsafe {
@@ -584,7 +1324,10 @@ impl Usrp {
         Ok(value)
     }
 
-    /// Returns the ranges of supported sample rates
+    /// Returns the ranges of supported transmit sample rates
+    ///
+    /// On some front ends these are different from the supported receive rates. A requested
+    /// rate can be checked against this range with MetaRange::clip().
     pub fn get_tx_sample_rates(&self, channel: usize) -> Result<MetaRange, Error> {
         let mut range = MetaRange::new();
         check_status(unsafe {
@@ -594,6 +1337,10 @@ impl Usrp {
     }
 
     /// Returns the USRP's current time. Commands can be scheduled relative to this time.
+    ///
+    /// The C API provides the time only as whole seconds and a floating-point fraction, which
+    /// are copied into the TimeSpec without rounding. TimeSpec::to_ticks() converts the time
+    /// into ticks of the device clock.
     pub fn get_current_time(&self, mboard: usize) -> Result<TimeSpec, Error> {
         let mut time = TimeSpec::default();
         let mut seconds_time_t: libc::time_t = Default::default();
@@ -610,19 +1357,266 @@ impl Usrp {
         Ok(time)
     }
 
+    /// Returns true if the provided time is not after the current time of a motherboard
+    ///
+    /// A command or burst scheduled for a time in the past is either dropped or reported as
+    /// late, so this can be used to check a time before scheduling something. The device time
+    /// keeps advancing after this check, so a time that is only slightly in the future may
+    /// still be late when the command reaches the device.
+    pub fn is_time_past(&self, time: &TimeSpec, mboard: usize) -> Result<bool, Error> {
+        Ok(*time <= self.get_current_time(mboard)?)
+    }
+
+    /// Returns the start of the next whole second of a motherboard's time
+    ///
+    /// This is a convenient time to start a burst that should be aligned to a second boundary.
+    pub fn next_whole_second(&self, mboard: usize) -> Result<TimeSpec, Error> {
+        self.whole_seconds_ahead(1, mboard)
+    }
+
+    /// Returns the start of a whole second of a motherboard's time, counted from the current
+    /// second
+    ///
+    /// A value of 1 for seconds returns the start of the next second, like next_whole_second().
+    /// Larger values leave more time to prepare a burst before it starts.
+    pub fn whole_seconds_ahead(&self, seconds: i64, mboard: usize) -> Result<TimeSpec, Error> {
+        let now = self.get_current_time(mboard)?;
+        Ok(TimeSpec {
+            seconds: now.seconds + seconds,
+            fraction: 0.0,
+        })
+    }
+
+    /// Returns the time that the USRP's clock had at the most recent PPS edge
+    pub fn get_time_last_pps(&self, mboard: usize) -> Result<TimeSpec, Error> {
+        let mut time = TimeSpec::default();
+        let mut seconds_time_t: libc::time_t = Default::default();
+
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_get_time_last_pps(
+                self.0,
+                mboard as _,
+                &mut seconds_time_t,
+                &mut time.fraction,
+            )
+        })?;
+        time.seconds = seconds_time_t.into();
+        Ok(time)
+    }
+
+    /// Returns true if the times of all motherboards at the last PPS edge are equal (within
+    /// one microsecond)
+    ///
+    /// This can be used to check that all devices in a multi-USRP system have been
+    /// synchronized to the same PPS signal.
+    pub fn times_synchronized(&self) -> Result<bool, Error> {
+        const TOLERANCE: f64 = 1e-6;
+        let first = self.get_time_last_pps(0)?;
+        for mboard in 1..self.get_num_motherboards()? {
+            let time = self.get_time_last_pps(mboard)?;
+            let difference =
+                (time.seconds - first.seconds) as f64 + (time.fraction - first.fraction);
+            if difference.abs() > TOLERANCE {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
     /// Returns the current clock source
     pub fn set_clock_source(&self, source: &str, mboard: usize) -> Result<(), Error> {
         let source = CString::new(source)?;
-        check_status(unsafe { uhd_sys::uhd_usrp_set_clock_source(self.0, source.as_ptr(), mboard as _) })
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_clock_source(self.0, source.as_ptr(), mboard as _)
+        })
+    }
+
+    /// Enables or disables the reference clock output of a motherboard
+    ///
+    /// In a daisy-chained system, enabling this output on the first device lets it provide
+    /// the reference clock to the next device.
+    pub fn set_clock_source_out(&mut self, enable: bool, mboard: usize) -> Result<(), Error> {
+        check_status(unsafe { uhd_sys::uhd_usrp_set_clock_source_out(self.0, enable, mboard as _) })
+    }
+
+    /// Sets the time when the following commands will take effect
+    ///
+    /// Settings that support timed commands, such as frequency and gain, are applied when the
+    /// USRP's clock reaches this time. Use Usrp::ALL_MBOARDS to set the command time on all
+    /// motherboards, and clear_command_time() to return to applying commands immediately.
+    ///
+    /// This function returns Error::InvalidTimeSpec if the fraction of the time is not in
+    /// the range [0, 1).
+    pub fn set_command_time(&mut self, time: &TimeSpec, mboard: usize) -> Result<(), Error> {
+        time.check_fraction()?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_command_time(
+                self.0,
+                time.seconds as _,
+                time.fraction,
+                mboard as _,
+            )
+        })
+    }
+
+    /// Sets the time source
+    pub fn set_time_source(&mut self, source: &str, mboard: usize) -> Result<(), Error> {
+        let source = CString::new(source)?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_time_source(self.0, source.as_ptr(), mboard as _)
+        })
+    }
+
+    /// Enables or disables the PPS (time) output of a motherboard
+    ///
+    /// In a daisy-chained system, enabling this output on the first device lets it provide
+    /// the PPS signal to the next device.
+    pub fn set_time_source_out(&mut self, enable: bool, mboard: usize) -> Result<(), Error> {
+        check_status(unsafe { uhd_sys::uhd_usrp_set_time_source_out(self.0, enable, mboard as _) })
+    }
+
+    /// Sets the clock source and time source of all motherboards to a consistent configuration
+    ///
+    /// For all configurations except ClockSync::Internal, this function then waits up to
+    /// lock_timeout for each motherboard that has a `ref_locked` sensor to lock to the
+    /// reference (see wait_ref_locked()), and returns Error::LockTimeout if a motherboard
+    /// has not locked. Motherboards without a `ref_locked` sensor are not checked.
+    /// Usrp::REF_LOCK_TIMEOUT is long enough for most devices.
+    pub fn configure_clock_sync(
+        &mut self,
+        sync: ClockSync,
+        lock_timeout: Duration,
+    ) -> Result<(), Error> {
+        for mboard in 0..self.get_num_motherboards()? {
+            self.set_clock_source(sync.clock_source(), mboard)?;
+            self.set_time_source(sync.time_source(), mboard)?;
+        }
+        if sync != ClockSync::Internal {
+            for mboard in 0..self.get_num_motherboards()? {
+                if !self.has_mboard_sensor("ref_locked", mboard)? {
+                    continue;
+                }
+                self.wait_ref_locked(lock_timeout, mboard)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Waits until a motherboard has locked to its reference clock
+    ///
+    /// This function polls the `ref_locked` sensor every 10 milliseconds. After selecting an
+    /// external reference, the motherboard's PLL can take up to several hundred milliseconds
+    /// to lock.
+    ///
+    /// This function returns Error::MissingSensor if the motherboard has no `ref_locked`
+    /// sensor, or Error::LockTimeout with the time spent waiting if the motherboard has not
+    /// locked when the timeout expires.
+    pub fn wait_ref_locked(&self, timeout: Duration, mboard: usize) -> Result<(), Error> {
+        const SENSOR: &str = "ref_locked";
+        if !self.has_mboard_sensor(SENSOR, mboard)? {
+            return Err(Error::MissingSensor {
+                name: SENSOR.to_owned(),
+                mboard,
+            });
+        }
+        let start = Instant::now();
+        loop {
+            if self.get_mboard_sensor(SENSOR, mboard)? == SensorValue::Bool(true) {
+                return Ok(());
+            }
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                return Err(Error::LockTimeout { mboard, elapsed });
+            }
+            thread::sleep(Duration::from_millis(10).min(timeout - elapsed));
+        }
+    }
+
+    /// Sets the USRP's current time
+    ///
+    /// This function returns Error::InvalidTimeSpec if the fraction of the time is not in
+    /// the range [0, 1).
+    pub fn set_time_now(&mut self, time: &TimeSpec, mboard: usize) -> Result<(), Error> {
+        time.check_fraction()?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_time_now(self.0, time.seconds as _, time.fraction, mboard as _)
+        })
+    }
+
+    /// Sets the time of a motherboard at its next PPS edge
+    ///
+    /// Use Usrp::ALL_MBOARDS to set the time of all motherboards at the same edge. This
+    /// function returns Error::InvalidTimeSpec if the fraction of the time is not in the
+    /// range [0, 1).
+    pub fn set_time_next_pps(&mut self, time: &TimeSpec, mboard: usize) -> Result<(), Error> {
+        time.check_fraction()?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_time_next_pps(
+                self.0,
+                time.seconds as _,
+                time.fraction,
+                mboard as _,
+            )
+        })
     }
-    
+
+    /// Sets the time of a motherboard at its next PPS edge, and checks that the time was set
+    ///
+    /// This function waits for a PPS edge, calls set_time_next_pps() just after it, waits just
+    /// over one second for the following edge, and then checks that the time at the last PPS
+    /// edge is equal to the provided time (within one microsecond). It takes up to about
+    /// 2.5 seconds. Use Usrp::ALL_MBOARDS to set and check the time of all motherboards.
+    ///
+    /// This function returns Error::Timeout if no PPS edge arrives within 1.5 seconds, or
+    /// Error::PpsTimeNotSet if a motherboard missed the edge (for example, because of noise on
+    /// the PPS input).
+    pub fn set_time_and_verify(&mut self, time: &TimeSpec, mboard: usize) -> Result<(), Error> {
+        const TOLERANCE: f64 = 1e-6;
+        let mboards = if mboard == Usrp::ALL_MBOARDS {
+            (0..self.get_num_motherboards()?).collect()
+        } else {
+            vec![mboard]
+        };
+        let first_mboard = mboards.first().copied().unwrap_or(0);
+
+        // Start just after an edge, so that exactly one edge follows in the next second
+        let start = Instant::now();
+        let last_pps = self.get_time_last_pps(first_mboard)?;
+        while self.get_time_last_pps(first_mboard)? == last_pps {
+            if start.elapsed() > Duration::from_millis(1500) {
+                return Err(Error::Timeout);
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        self.set_time_next_pps(time, mboard)?;
+        thread::sleep(Duration::from_millis(1100));
+
+        for mboard in mboards {
+            let difference = self.get_time_last_pps(mboard)? - time.clone();
+            if (difference.seconds as f64 + difference.fraction).abs() > TOLERANCE {
+                return Err(Error::PpsTimeNotSet(mboard));
+            }
+        }
+        Ok(())
+    }
+
     /// Enables or disables the receive automatic gain control
     pub fn set_rx_agc_enabled(&mut self, enabled: bool, channel: usize) -> Result<(), Error> {
         check_status(unsafe { uhd_sys::uhd_usrp_set_rx_agc(self.0, enabled, channel as _) })
     }
 
     /// Sets the antenna used to receive
+    ///
+    /// This function returns Error::InvalidAntenna if the antenna is not one of the antennas
+    /// that get_rx_antennas() returns.
     pub fn set_rx_antenna(&mut self, antenna: &str, channel: usize) -> Result<(), Error> {
+        let available = self.get_rx_antennas(channel)?;
+        if !available.iter().any(|name| name == antenna) {
+            return Err(Error::InvalidAntenna {
+                antenna: antenna.to_owned(),
+                available,
+            });
+        }
         let antenna = CString::new(antenna)?;
         check_status(unsafe {
             uhd_sys::uhd_usrp_set_rx_antenna(self.0, antenna.as_ptr(), channel as _)
@@ -630,18 +1624,50 @@ impl Usrp {
     }
 
     /// Sets the receive bandwidth
+    ///
+    /// The device may round the bandwidth to a value that its filters support. The applied
+    /// bandwidth can be read back with get_rx_bandwidth().
     pub fn set_rx_bandwidth(&mut self, bandwidth: f64, channel: usize) -> Result<(), Error> {
         check_status(unsafe { uhd_sys::uhd_usrp_set_rx_bandwidth(self.0, bandwidth, channel as _) })
     }
 
     /// Enables or disables DC offset correction
+    ///
+    /// UHD does not provide a function to read this setting back. On some devices, tuning
+    /// disables automatic correction again; set_rx_frequency_dc_corrected() re-enables it after
+    /// each retune.
     pub fn set_rx_dc_offset_enabled(&mut self, enabled: bool, channel: usize) -> Result<(), Error> {
         check_status(unsafe {
             uhd_sys::uhd_usrp_set_rx_dc_offset_enabled(self.0, enabled, channel as _)
         })
     }
 
+    /// Enables or disables automatic IQ imbalance correction
+    ///
+    /// UHD does not provide a function to read this setting back.
+    pub fn set_rx_iq_balance_enabled(
+        &mut self,
+        enabled: bool,
+        channel: usize,
+    ) -> Result<(), Error> {
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_rx_iq_balance_enabled(self.0, enabled, channel as _)
+        })
+    }
+
+    /// Applies automatic DC offset and IQ imbalance correction settings to a receive channel
+    pub fn set_rx_frontend_corrections(
+        &mut self,
+        corrections: &FrontendCorrections,
+        channel: usize,
+    ) -> Result<(), Error> {
+        self.set_rx_dc_offset_enabled(corrections.dc_offset_enabled, channel)?;
+        self.set_rx_iq_balance_enabled(corrections.iq_balance_enabled, channel)
+    }
+
     /// Sets the receive center frequency
+    ///
+    /// The returned TuneResult contains the RF and DSP frequencies that were actually applied.
     pub fn set_rx_frequency(
         &mut self,
         request: &TuneRequest,
@@ -667,6 +1693,117 @@ impl Usrp {
         Ok(result)
     }
 
+    /// Tunes several receive channels to the same RF center frequency, with a different DSP
+    /// offset on each channel, and returns the result of tuning each channel
+    ///
+    /// Channel i is tuned to center + offsets\[i\], using an RF frequency of center. This can
+    /// be used to receive several narrow channels within one band.
+    ///
+    /// Before tuning any channel, this function returns Error::InvalidChannel if there are more
+    /// offsets than receive channels, and Error::DspOffsetOutOfRange if an offset is outside
+    /// the range from get_rx_dsp_freq_range(). That range is an estimate, so an offset near its
+    /// limits may be rejected even though the device could use it.
+    pub fn set_rx_frequency_coherent(
+        &mut self,
+        center: f64,
+        offsets: &[f64],
+    ) -> Result<Vec<TuneResult>, Error> {
+        if let Some(last) = offsets.len().checked_sub(1) {
+            self.rx_channel(last)?;
+        }
+        for (channel, &offset) in offsets.iter().enumerate() {
+            let range = self.get_rx_dsp_freq_range(channel)?;
+            if offset < range.start()? || offset > range.stop()? {
+                return Err(Error::DspOffsetOutOfRange {
+                    channel,
+                    offset,
+                    limit: range.stop()?,
+                });
+            }
+        }
+        offsets
+            .iter()
+            .enumerate()
+            .map(|(channel, &offset)| {
+                let mut request = TuneRequest::with_frequency(center + offset);
+                request.set_rf_policy(TuneRequestPolicy::Manual(center));
+                self.set_rx_frequency(&request, channel)
+            })
+            .collect()
+    }
+
+    /// Sets the receive center frequency, and then enables automatic DC offset correction
+    ///
+    /// UHD does not support enabling DC offset correction only for some frequencies. This
+    /// function can be used for every retune, so that correction is active after each one.
+    pub fn set_rx_frequency_dc_corrected(
+        &mut self,
+        request: &TuneRequest,
+        channel: usize,
+    ) -> Result<TuneResult, Error> {
+        let result = self.set_rx_frequency(request, channel)?;
+        self.set_rx_dc_offset_enabled(true, channel)?;
+        Ok(result)
+    }
+
+    /// Sets the receive center frequency, and then waits until the local oscillator has locked
+    ///
+    /// Samples received before the local oscillator locks are not at the requested
+    /// frequency. This function polls the `lo_locked` sensor of the channel every millisecond.
+    /// If the channel has no `lo_locked` sensor, it returns immediately after tuning.
+    ///
+    /// This function returns Error::LoLockTimeout if the local oscillator has not locked when
+    /// settle_timeout expires. Samples that were already received by a running stream should
+    /// be discarded, for example with ReceiveStreamer::flush().
+    pub fn tune_rx_and_settle(
+        &mut self,
+        request: &TuneRequest,
+        channel: usize,
+        settle_timeout: Duration,
+    ) -> Result<TuneResult, Error> {
+        const SENSOR: &str = "lo_locked";
+        let result = self.set_rx_frequency(request, channel)?;
+        if !self
+            .get_rx_sensor_names(channel)?
+            .iter()
+            .any(|name| name == SENSOR)
+        {
+            return Ok(result);
+        }
+        let start = Instant::now();
+        loop {
+            if self.get_rx_sensor(SENSOR, channel)? == SensorValue::Bool(true) {
+                return Ok(result);
+            }
+            let elapsed = start.elapsed();
+            if elapsed >= settle_timeout {
+                return Err(Error::LoLockTimeout { channel, elapsed });
+            }
+            thread::sleep(Duration::from_millis(1).min(settle_timeout - elapsed));
+        }
+    }
+
+    /// Sets the receive center frequency at a specific time, according to the USRP's clock
+    ///
+    /// This function sets the command time of all motherboards, tunes, and then clears the
+    /// command time. The command time is cleared even if tuning fails.
+    ///
+    /// This function returns Error::InvalidTimeSpec if the fraction of the time is not in
+    /// the range [0, 1).
+    pub fn schedule_retune(
+        &mut self,
+        request: &TuneRequest,
+        at: &TimeSpec,
+        channel: usize,
+    ) -> Result<TuneResult, Error> {
+        self.set_command_time(at, Usrp::ALL_MBOARDS)?;
+        let result = self.set_rx_frequency(request, channel);
+        let clear_result = self.clear_command_time(Usrp::ALL_MBOARDS);
+        let result = result?;
+        clear_result?;
+        Ok(result)
+    }
+
     /// Sets the receive gain
     pub fn set_rx_gain(&mut self, gain: f64, channel: usize, name: &str) -> Result<(), Error> {
         let name = CString::new(name)?;
@@ -675,13 +1812,177 @@ impl Usrp {
         })
     }
 
+    /// Sets the overall receive gain, clipped to the gain range, and returns the gain that
+    /// was set
+    ///
+    /// Depending on the device, UHD either returns an error or silently saturates when a gain
+    /// is outside the range. This function clips the gain to the range from
+    /// get_rx_gain_range() and rounds it to the step of the range first, so all devices
+    /// behave the same way.
+    pub fn set_rx_gain_clamped(&mut self, gain: f64, channel: usize) -> Result<f64, Error> {
+        // An empty name refers to the overall gain
+        let gain = self.get_rx_gain_range(channel, "")?.clip(gain, true)?;
+        self.set_rx_gain(gain, channel, "")?;
+        Ok(gain)
+    }
+
+    /// Sets the overall receive gain to a fraction of its range in decibels, and returns the
+    /// gain that was set
+    ///
+    /// A fraction of 0.0 sets the minimum gain and 1.0 sets the maximum gain. The gain is linear
+    /// in decibels between those points and is rounded to the step of the gain range. This is
+    /// different from normalized gain, which UHD may map to decibels in a device-specific way.
+    ///
+    /// This function returns Error::Value if fraction is not in the range [0, 1].
+    pub fn set_rx_gain_fraction(&mut self, fraction: f64, channel: usize) -> Result<f64, Error> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(Error::Value);
+        }
+        // An empty name refers to the overall gain
+        let range = self.get_rx_gain_range(channel, "")?;
+        let start = range.start()?;
+        let gain = range.clip(start + fraction * (range.stop()? - start), true)?;
+        self.set_rx_gain(gain, channel, "")?;
+        Ok(gain)
+    }
+
+    /// Sets the frequency of a local oscillator, and returns the frequency that the
+    /// synthesizer actually produces
+    ///
+    /// The frequency is applied only when the local oscillator is not tuned automatically.
+    pub fn set_rx_lo_frequency(
+        &mut self,
+        frequency: f64,
+        name: &str,
+        channel: usize,
+    ) -> Result<f64, Error> {
+        let name = CString::new(name)?;
+        let mut coerced = 0.0;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_rx_lo_freq(
+                self.0,
+                frequency,
+                name.as_ptr(),
+                channel as _,
+                &mut coerced,
+            )
+        })?;
+        Ok(coerced)
+    }
+
+    /// Enables or disables exporting a local oscillator signal to other channels or devices
+    pub fn set_rx_lo_export_enabled(
+        &mut self,
+        enabled: bool,
+        name: &str,
+        channel: usize,
+    ) -> Result<(), Error> {
+        let name = CString::new(name)?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_rx_lo_export_enabled(self.0, enabled, name.as_ptr(), channel as _)
+        })
+    }
+
+    /// Sets the source of a local oscillator
+    ///
+    /// The available sources can be found with get_rx_lo_sources().
+    pub fn set_rx_lo_source(
+        &mut self,
+        source: &str,
+        name: &str,
+        channel: usize,
+    ) -> Result<(), Error> {
+        let source = CString::new(source)?;
+        let name = CString::new(name)?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_rx_lo_source(self.0, source.as_ptr(), name.as_ptr(), channel as _)
+        })
+    }
+
+    /// Sets the subdevice specification that maps receive channels to front ends on a
+    /// motherboard
+    pub fn set_rx_subdev_spec(&mut self, spec: &SubdevSpec, mboard: usize) -> Result<(), Error> {
+        let spec = SubdevSpecHandle::new(spec)?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_rx_subdev_spec(self.0, spec.handle(), mboard as _)
+        })
+    }
+
+    /// Configures a two-channel receiver on a motherboard with two front ends on daughterboard
+    /// slot A, such as a B210
+    ///
+    /// This sets the receive subdevice specification of the motherboard to `A:A A:B` and
+    /// selects the `RX2` antenna on the two receive channels of the motherboard. The front end
+    /// and antenna names are specific to devices like the B210, so other devices may return an
+    /// error. With several motherboards, the channels of this motherboard come after the
+    /// channels of the motherboards before it.
+    ///
+    /// This function returns Error::InvalidChannel if the motherboard has only one receive
+    /// channel after the subdevice specification is set.
+    pub fn configure_dual_rx(&mut self, mboard: usize) -> Result<(), Error> {
+        let spec: SubdevSpec = "A:A A:B".parse()?;
+        self.set_rx_subdev_spec(&spec, mboard)?;
+        let counts = self.rx_channels_per_mboard()?;
+        let first: usize = counts.iter().take(mboard).sum();
+        if counts.get(mboard).copied().unwrap_or(0) < 2 {
+            return Err(Error::InvalidChannel {
+                channel: first + 1,
+                num_channels: self.get_num_rx_channels()?,
+            });
+        }
+        for channel in first..first + 2 {
+            self.set_rx_antenna("RX2", channel)?;
+        }
+        Ok(())
+    }
+
     /// Sets the receive sample rate
+    ///
+    /// On some devices, several channels share one DDC or one master clock divider, so setting
+    /// the rate of one channel also changes the rate of other channels. The device may also
+    /// round the rate. The applied rate of each channel can be read back with
+    /// get_rx_sample_rate(), or set_rx_sample_rate_all_checked() can be used to set the rate
+    /// of all channels together.
     pub fn set_rx_sample_rate(&mut self, rate: f64, channel: usize) -> Result<(), Error> {
         check_status(unsafe { uhd_sys::uhd_usrp_set_rx_rate(self.0, rate, channel as _) })
     }
 
+    /// Sets the sample rate of all receive channels, checks that all channels have the same
+    /// applied rate, and returns that rate
+    ///
+    /// This function returns Error::RateMismatch, with the applied rate of each channel, if
+    /// the channels have different rates afterwards.
+    pub fn set_rx_sample_rate_all_checked(&mut self, rate: f64) -> Result<f64, Error> {
+        let num_channels = self.get_num_rx_channels()?;
+        for channel in 0..num_channels {
+            self.set_rx_sample_rate(rate, channel)?;
+        }
+        let rates = (0..num_channels)
+            .map(|channel| self.get_rx_sample_rate(channel))
+            .collect::<Result<Vec<f64>, Error>>()?;
+        let first = rates.first().copied().unwrap_or(rate);
+        if rates
+            .iter()
+            .all(|rate| (rate - first).abs() <= first.abs() * 1e-9)
+        {
+            Ok(first)
+        } else {
+            Err(Error::RateMismatch { rates })
+        }
+    }
+
     /// Sets the antenna used to transmit
+    ///
+    /// This function returns Error::InvalidAntenna if the antenna is not one of the antennas
+    /// that get_tx_antennas() returns.
     pub fn set_tx_antenna(&mut self, antenna: &str, channel: usize) -> Result<(), Error> {
+        let available = self.get_tx_antennas(channel)?;
+        if !available.iter().any(|name| name == antenna) {
+            return Err(Error::InvalidAntenna {
+                antenna: antenna.to_owned(),
+                available,
+            });
+        }
         let antenna = CString::new(antenna)?;
         check_status(unsafe {
             uhd_sys::uhd_usrp_set_tx_antenna(self.0, antenna.as_ptr(), channel as _)
@@ -689,11 +1990,16 @@ impl Usrp {
     }
 
     /// Sets the transmit bandwidth
+    ///
+    /// The device may round the bandwidth to a value that its filters support. The applied
+    /// bandwidth can be read back with get_tx_bandwidth().
     pub fn set_tx_bandwidth(&mut self, bandwidth: f64, channel: usize) -> Result<(), Error> {
         check_status(unsafe { uhd_sys::uhd_usrp_set_tx_bandwidth(self.0, bandwidth, channel as _) })
     }
 
     /// Sets the transmit center frequency
+    ///
+    /// The returned TuneResult contains the RF and DSP frequencies that were actually applied.
     pub fn set_tx_frequency(
         &mut self,
         request: &TuneRequest,
@@ -727,6 +2033,68 @@ impl Usrp {
         })
     }
 
+    /// Sets the frequency of a local oscillator, and returns the frequency that the
+    /// synthesizer actually produces
+    ///
+    /// The frequency is applied only when the local oscillator is not tuned automatically.
+    pub fn set_tx_lo_frequency(
+        &mut self,
+        frequency: f64,
+        name: &str,
+        channel: usize,
+    ) -> Result<f64, Error> {
+        let name = CString::new(name)?;
+        let mut coerced = 0.0;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_tx_lo_freq(
+                self.0,
+                frequency,
+                name.as_ptr(),
+                channel as _,
+                &mut coerced,
+            )
+        })?;
+        Ok(coerced)
+    }
+
+    /// Sets the source of a local oscillator
+    ///
+    /// The available sources can be found with get_tx_lo_sources().
+    pub fn set_tx_lo_source(
+        &mut self,
+        source: &str,
+        name: &str,
+        channel: usize,
+    ) -> Result<(), Error> {
+        let source = CString::new(source)?;
+        let name = CString::new(name)?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_tx_lo_source(self.0, source.as_ptr(), name.as_ptr(), channel as _)
+        })
+    }
+
+    /// Enables or disables exporting a local oscillator signal to other channels or devices
+    pub fn set_tx_lo_export_enabled(
+        &mut self,
+        enabled: bool,
+        name: &str,
+        channel: usize,
+    ) -> Result<(), Error> {
+        let name = CString::new(name)?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_tx_lo_export_enabled(self.0, enabled, name.as_ptr(), channel as _)
+        })
+    }
+
+    /// Sets the subdevice specification that maps transmit channels to front ends on a
+    /// motherboard
+    pub fn set_tx_subdev_spec(&mut self, spec: &SubdevSpec, mboard: usize) -> Result<(), Error> {
+        let spec = SubdevSpecHandle::new(spec)?;
+        check_status(unsafe {
+            uhd_sys::uhd_usrp_set_tx_subdev_spec(self.0, spec.handle(), mboard as _)
+        })
+    }
+
     /// Sets the transmit sample rate
     pub fn set_tx_sample_rate(&mut self, rate: f64, channel: usize) -> Result<(), Error> {
         check_status(unsafe { uhd_sys::uhd_usrp_set_tx_rate(self.0, rate, channel as _) })
@@ -740,6 +2108,31 @@ impl Usrp {
         })?;
         Ok(banks.into())
     }
+
+    /// Returns the underlying UHD handle, for calling UHD functions that this library does not
+    /// wrap
+    ///
+    /// # Safety
+    ///
+    /// The handle must not be freed, and must not be used after this Usrp is dropped. Calls
+    /// through the handle must follow the same threading rules as the functions of this struct.
+    #[cfg(feature = "raw-handle")]
+    pub unsafe fn raw_handle(&self) -> uhd_sys::uhd_usrp_handle {
+        self.0
+    }
+}
+
+/// Converts an error from creating a stream into Error::UnsupportedFormat if the UHD error
+/// message shows that the stream formats are not supported
+fn stream_format_error(error: Error, args: &StreamArgsC<'_>) -> Error {
+    match last_error_message() {
+        Some(detail) if is_unsupported_format(&detail) => Error::UnsupportedFormat {
+            host_format: args.host_format_name,
+            wire_format: args.wire_format.to_string_lossy().into_owned(),
+            detail,
+        },
+        _ => error,
+    }
 }
 
 impl Drop for Usrp {
@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use std::os::raw::c_char;
 
 use crate::error::{check_status, Error};
@@ -70,6 +71,30 @@ where
         .unwrap_or(0)
 }
 
+/// Converts a buffer length into the integer type that a C function takes
+///
+/// This returns Error::BufferTooLarge instead of truncating the length if it does not fit
+/// (for example, where size_t is a 32-bit unsigned long).
+pub(crate) fn c_buffer_length<T>(length: usize) -> Result<T, Error>
+where
+    T: TryFrom<usize>,
+{
+    T::try_from(length).map_err(|_| Error::BufferTooLarge { length })
+}
+
+/// Returns a receive or transmit timeout that allows for two packets of max_num_samps samples
+/// at a sample rate, but is at least 10 milliseconds
+///
+/// If the rate is not positive, this returns 0.1 seconds.
+pub(crate) fn packet_timeout(max_num_samps: usize, rate: f64) -> f64 {
+    const MIN_TIMEOUT: f64 = 0.01;
+    if rate > 0.0 {
+        (2.0 * max_num_samps as f64 / rate).max(MIN_TIMEOUT)
+    } else {
+        0.1
+    }
+}
+
 /// An iterator over buffer sizes that yields INITIAL_SIZE and then double the previous value
 /// up to MAX_SIZE
 struct BufferSizes {
@@ -128,4 +153,23 @@ mod tests {
         assert_eq!(Some(1048576), sizes.next());
         assert_eq!(None, sizes.next());
     }
+
+    #[test]
+    fn timeout_from_rate() {
+        // 2000 samples at 100 kS/s take 20 ms per packet
+        assert!((packet_timeout(2000, 100e3) - 0.04).abs() < 1e-12);
+        assert_eq!(packet_timeout(2000, 200e6), 0.01);
+        assert_eq!(packet_timeout(2000, 0.0), 0.1);
+    }
+
+    #[test]
+    fn buffer_length_checked() {
+        assert_eq!(c_buffer_length::<u32>(1024).unwrap(), 1024);
+        // A 32-bit C type stands in for a platform where size_t is smaller than usize
+        let length = u32::MAX as usize + 1;
+        match c_buffer_length::<u32>(length) {
+            Err(Error::BufferTooLarge { length: reported }) => assert_eq!(reported, length),
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
 }
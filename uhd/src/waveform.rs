@@ -0,0 +1,368 @@
+//!
+//! Generators for test signals to transmit
+//!
+
+use std::f64::consts::PI;
+
+use num_complex::{Complex, Complex32, Complex64};
+
+use crate::utils::check_equal_buffer_lengths;
+
+/// A source of samples that can fill transmit buffers
+///
+/// Each call to fill() continues the waveform from where the previous call ended, so
+/// successive buffers can be transmitted without discontinuities.
+pub trait Waveform<I> {
+    /// Fills the buffer with the next samples of the waveform
+    fn fill(&mut self, buffer: &mut [I]);
+}
+
+/// A sample type that a waveform can be converted into
+///
+/// Floating-point samples are copied directly. Integer samples are scaled so that a magnitude
+/// of 1.0 corresponds to the largest integer value.
+pub trait WaveformItem {
+    /// Converts a sample with real and imaginary parts in the range [-1, 1]
+    fn from_complex64(value: Complex64) -> Self;
+}
+
+impl WaveformItem for Complex64 {
+    fn from_complex64(value: Complex64) -> Self {
+        value
+    }
+}
+
+impl WaveformItem for Complex32 {
+    fn from_complex64(value: Complex64) -> Self {
+        Complex32::new(value.re as f32, value.im as f32)
+    }
+}
+
+impl WaveformItem for Complex<i16> {
+    fn from_complex64(value: Complex64) -> Self {
+        let scale = f64::from(i16::MAX);
+        Complex::new((value.re * scale) as i16, (value.im * scale) as i16)
+    }
+}
+
+impl WaveformItem for Complex<i8> {
+    fn from_complex64(value: Complex64) -> Self {
+        let scale = f64::from(i8::MAX);
+        Complex::new((value.re * scale) as i8, (value.im * scale) as i8)
+    }
+}
+
+/// Adds an angle to a phase and wraps the result into the range [0, 2 pi)
+///
+/// Keeping the phase small preserves its precision over long transmissions.
+fn advance_phase(phase: f64, step: f64) -> f64 {
+    (phase + step).rem_euclid(2.0 * PI)
+}
+
+/// Fills one buffer per channel from a waveform, with a phase offset on each channel
+///
+/// All channels receive the same samples of the waveform, each multiplied by
+/// e^(j * phase_offset) for its channel, in radians. This can be used to test beam steering
+/// with several transmit channels. The waveform advances by the length of one buffer.
+///
+/// # Panics
+///
+/// This function panics if the number of phase offsets is not equal to the number of buffers,
+/// or if the buffers do not all have the same length.
+pub fn fill_phased<W, I>(waveform: &mut W, phase_offsets: &[f64], buffers: &mut [&mut [I]])
+where
+    W: Waveform<Complex64>,
+    I: WaveformItem,
+{
+    assert_eq!(
+        phase_offsets.len(),
+        buffers.len(),
+        "Number of phase offsets not equal to number of buffers"
+    );
+    let length = check_equal_buffer_lengths(buffers);
+    let mut samples = vec![Complex64::default(); length];
+    waveform.fill(&mut samples);
+    for (buffer, phase_offset) in buffers.iter_mut().zip(phase_offsets) {
+        let rotation = Complex64::from_polar(1.0, *phase_offset);
+        for (output, sample) in buffer.iter_mut().zip(&samples) {
+            *output = I::from_complex64(sample * rotation);
+        }
+    }
+}
+
+/// A continuous-wave complex tone
+#[derive(Debug, Clone)]
+pub struct ToneGenerator {
+    /// Phase change per sample, in radians
+    phase_step: f64,
+    /// Phase of the next sample, in radians
+    phase: f64,
+    /// Magnitude of each sample
+    amplitude: f64,
+}
+
+impl ToneGenerator {
+    /// Creates a tone with the provided frequency, in hertz, at the provided sample rate,
+    /// in samples per second
+    ///
+    /// The tone has an amplitude of 1.0. Negative frequencies are allowed.
+    pub fn new(frequency: f64, sample_rate: f64) -> Self {
+        ToneGenerator {
+            phase_step: 2.0 * PI * frequency / sample_rate,
+            phase: 0.0,
+            amplitude: 1.0,
+        }
+    }
+
+    /// Creates a tone with a frequency in cycles per sample
+    ///
+    /// The frequency should be in the range [-0.5, 0.5]. The tone has an amplitude of 1.0.
+    pub fn with_normalized_frequency(frequency: f64) -> Self {
+        ToneGenerator::new(frequency, 1.0)
+    }
+
+    /// Changes the frequency of the tone, in hertz, at the provided sample rate
+    ///
+    /// The phase continues from the last sample generated, so the change does not cause
+    /// a discontinuity.
+    pub fn set_frequency(&mut self, frequency: f64, sample_rate: f64) {
+        self.phase_step = 2.0 * PI * frequency / sample_rate;
+    }
+
+    /// Changes the frequency of the tone, in cycles per sample, without a phase discontinuity
+    pub fn set_normalized_frequency(&mut self, frequency: f64) {
+        self.set_frequency(frequency, 1.0)
+    }
+
+    /// Sets the magnitude of the tone, which should be in the range [0, 1]
+    pub fn with_amplitude(self, amplitude: f64) -> Self {
+        ToneGenerator { amplitude, ..self }
+    }
+}
+
+impl<I> Waveform<I> for ToneGenerator
+where
+    I: WaveformItem,
+{
+    fn fill(&mut self, buffer: &mut [I]) {
+        for sample in buffer {
+            *sample = I::from_complex64(Complex64::from_polar(self.amplitude, self.phase));
+            self.phase = advance_phase(self.phase, self.phase_step);
+        }
+    }
+}
+
+/// A linear frequency sweep that repeats
+///
+/// The frequency increases (or decreases) linearly from the start frequency to the stop
+/// frequency, and then jumps back to the start frequency. The phase is continuous.
+#[derive(Debug, Clone)]
+pub struct Chirp {
+    start_frequency: f64,
+    stop_frequency: f64,
+    sample_rate: f64,
+    /// Number of samples in one sweep
+    sweep_samples: u64,
+    /// Index of the next sample within the current sweep
+    index: u64,
+    /// Phase of the next sample, in radians
+    phase: f64,
+}
+
+impl Chirp {
+    /// Creates a chirp that sweeps from start_frequency to stop_frequency (in hertz) in
+    /// sweep_duration seconds, at the provided sample rate
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the sweep is shorter than one sample.
+    pub fn new(
+        start_frequency: f64,
+        stop_frequency: f64,
+        sweep_duration: f64,
+        sample_rate: f64,
+    ) -> Self {
+        let sweep_samples = (sweep_duration * sample_rate).round() as u64;
+        assert!(sweep_samples > 0, "Chirp sweep shorter than one sample");
+        Chirp {
+            start_frequency,
+            stop_frequency,
+            sample_rate,
+            sweep_samples,
+            index: 0,
+            phase: 0.0,
+        }
+    }
+
+    /// Returns the frequency of the next sample, in hertz
+    fn frequency(&self) -> f64 {
+        let progress = self.index as f64 / self.sweep_samples as f64;
+        self.start_frequency + (self.stop_frequency - self.start_frequency) * progress
+    }
+}
+
+impl<I> Waveform<I> for Chirp
+where
+    I: WaveformItem,
+{
+    fn fill(&mut self, buffer: &mut [I]) {
+        for sample in buffer {
+            *sample = I::from_complex64(Complex64::from_polar(1.0, self.phase));
+            let step = 2.0 * PI * self.frequency() / self.sample_rate;
+            self.phase = advance_phase(self.phase, step);
+            self.index = (self.index + 1) % self.sweep_samples;
+        }
+    }
+}
+
+/// A pseudo-random binary sequence (PRBS-15, polynomial x^15 + x^14 + 1), with each bit
+/// transmitted as one real-valued sample of +1 or -1
+///
+/// The sequence repeats every 32767 samples.
+#[derive(Debug, Clone)]
+pub struct Prbs {
+    /// Shift register state (15 bits, never zero)
+    state: u16,
+}
+
+impl Prbs {
+    /// The number of samples before the sequence repeats
+    pub const PERIOD: usize = (1 << 15) - 1;
+
+    /// Creates a sequence with the provided initial shift register state
+    ///
+    /// Only the lower 15 bits of the seed are used. If they are all zero, the seed is replaced
+    /// with all ones, because an all-zero state would produce only zeros.
+    pub fn new(seed: u16) -> Self {
+        let state = seed & 0x7fff;
+        Prbs {
+            state: if state == 0 { 0x7fff } else { state },
+        }
+    }
+
+    /// Returns the next bit of the sequence
+    fn next_bit(&mut self) -> bool {
+        let bit = ((self.state >> 14) ^ (self.state >> 13)) & 1;
+        self.state = ((self.state << 1) | bit) & 0x7fff;
+        bit == 1
+    }
+}
+
+impl Default for Prbs {
+    /// Creates a sequence with all ones as the initial state
+    fn default() -> Self {
+        Prbs::new(0x7fff)
+    }
+}
+
+impl<I> Waveform<I> for Prbs
+where
+    I: WaveformItem,
+{
+    fn fill(&mut self, buffer: &mut [I]) {
+        for sample in buffer {
+            let value = if self.next_bit() { 1.0 } else { -1.0 };
+            *sample = I::from_complex64(Complex64::new(value, 0.0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fill_phased, Chirp, Prbs, ToneGenerator, Waveform};
+    use num_complex::{Complex, Complex32, Complex64};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn tone_continuous_across_buffers() {
+        let mut whole = vec![Complex64::default(); 64];
+        ToneGenerator::new(1e3, 48e3).fill(&mut whole);
+
+        let mut generator = ToneGenerator::new(1e3, 48e3);
+        let mut parts = vec![Complex64::default(); 64];
+        let (first, second) = parts.split_at_mut(23);
+        generator.fill(first);
+        generator.fill(second);
+
+        for (a, b) in whole.iter().zip(parts.iter()) {
+            assert!((a - b).norm() < 1e-12);
+        }
+        // A quarter of a cycle after the start
+        assert!((whole[12] - Complex64::new(0.0, 1.0)).norm() < 1e-12);
+    }
+
+    #[test]
+    fn tone_frequency_change_keeps_phase() {
+        let mut generator = ToneGenerator::with_normalized_frequency(0.25);
+        let mut before = [Complex64::default(); 3];
+        generator.fill(&mut before);
+        // Phase is now 0.75 cycles
+        generator.set_normalized_frequency(-0.125);
+        let mut after = [Complex64::default(); 2];
+        generator.fill(&mut after);
+
+        let cycles = |sample: &Complex64| sample.arg().rem_euclid(2.0 * PI) / (2.0 * PI);
+        assert!((cycles(&before[2]) - 0.5).abs() < 1e-12);
+        assert!((cycles(&after[0]) - 0.75).abs() < 1e-12);
+        assert!((cycles(&after[1]) - 0.625).abs() < 1e-12);
+    }
+
+    #[test]
+    fn phased_channels() {
+        let mut generator = ToneGenerator::with_normalized_frequency(0.125);
+        let mut first = [Complex32::default(); 4];
+        let mut second = [Complex32::default(); 4];
+        fill_phased(
+            &mut generator,
+            &[0.0, PI / 2.0],
+            &mut [&mut first[..], &mut second[..]],
+        );
+        for (a, b) in first.iter().zip(second.iter()) {
+            // The second channel leads by a quarter of a cycle
+            assert!((a * Complex32::new(0.0, 1.0) - b).norm() < 1e-6);
+        }
+        // The waveform continues after the buffers
+        let mut next = [Complex64::default(); 1];
+        generator.fill(&mut next);
+        assert!((next[0] - Complex64::new(-1.0, 0.0)).norm() < 1e-12);
+    }
+
+    #[test]
+    fn integer_tone_scaled() {
+        let mut buffer = [Complex::<i16>::default(); 1];
+        ToneGenerator::new(0.0, 1.0)
+            .with_amplitude(0.5)
+            .fill(&mut buffer);
+        assert_eq!(buffer[0], Complex::new(16383, 0));
+    }
+
+    #[test]
+    fn chirp_phase_steps() {
+        // Sweep from 0 to 0.25 of the sample rate over 4 samples
+        let mut chirp = Chirp::new(0.0, 0.25, 4.0, 1.0);
+        let mut buffer = vec![Complex64::default(); 5];
+        chirp.fill(&mut buffer);
+        let phases: Vec<f64> = buffer
+            .iter()
+            .map(|sample| sample.arg().rem_euclid(2.0 * PI))
+            .collect();
+        let expected_cycles = [0.0, 0.0, 0.0625, 0.1875, 0.375];
+        for (phase, cycles) in phases.iter().zip(expected_cycles.iter()) {
+            assert!((phase - cycles * 2.0 * PI).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn prbs_period() {
+        let mut prbs = Prbs::default();
+        let mut buffer = vec![Complex64::default(); 2 * Prbs::PERIOD];
+        prbs.fill(&mut buffer);
+        assert_eq!(&buffer[..Prbs::PERIOD], &buffer[Prbs::PERIOD..]);
+        // A maximal-length sequence has one more 1 than -1 in each period
+        let ones = buffer[..Prbs::PERIOD]
+            .iter()
+            .filter(|sample| sample.re > 0.0)
+            .count();
+        assert_eq!(ones, Prbs::PERIOD / 2 + 1);
+    }
+}